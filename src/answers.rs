@@ -0,0 +1,97 @@
+use std::{collections::BTreeMap, fs};
+
+use aoc2019::{Input, Loader, Source};
+use serde::Deserialize;
+
+use crate::{DAYS, Solver};
+
+const ANSWERS: &str = "rsc/answers.toml";
+
+#[derive(Debug, Default, Deserialize)]
+struct Expected {
+    part1: Option<String>,
+    part2: Option<String>,
+}
+
+// Expected answers, indexed by day (`day1`) and then by input (`input`, `test` or `test_<name>`).
+type Answers = BTreeMap<String, BTreeMap<String, Expected>>;
+
+fn load_answers() -> Answers {
+    let content =
+        fs::read_to_string(ANSWERS).unwrap_or_else(|e| panic!("Could not read {}: {}", ANSWERS, e));
+    toml::from_str(&content).unwrap_or_else(|e| panic!("Could not parse {}: {}", ANSWERS, e))
+}
+
+fn source(day: usize, input: &str) -> Source {
+    match input {
+        "input" => Source::Puzzle,
+        "test" => Source::Example(None),
+        _ => match input.strip_prefix("test_") {
+            Some(name) => Source::Example(Some(name.to_owned())),
+            None => panic!("Unknown input '{}' for day {} in {}", input, day, ANSWERS),
+        },
+    }
+}
+
+fn check(part: u8, solver: Solver, input: &Input, expected: &Option<String>) -> Option<String> {
+    let expected = expected.as_ref()?;
+    match solver(input) {
+        Ok(answer) if &answer == expected => None,
+        Ok(answer) => Some(format!(
+            "part {}: expected {:?}, got {:?}",
+            part, expected, answer
+        )),
+        Err(e) => Some(format!(
+            "part {}: expected {:?}, failed: {}",
+            part, expected, e
+        )),
+    }
+}
+
+#[test]
+fn test_answers() {
+    let answers = load_answers();
+    let loader = Loader::default();
+
+    for key in answers.keys() {
+        let registered = key
+            .strip_prefix("day")
+            .and_then(|day| day.parse::<usize>().ok())
+            .is_some_and(|day| (1..=DAYS.len()).contains(&day));
+        assert!(
+            registered,
+            "{} lists answers for unknown day '{}'",
+            ANSWERS, key
+        );
+    }
+
+    let mut failures = Vec::new();
+    for (index, solvers) in DAYS.iter().enumerate() {
+        let day = index + 1;
+        let Some(inputs) = answers.get(&format!("day{}", day)) else {
+            eprintln!("day {}: no expected answers", day);
+            continue;
+        };
+
+        for (input, expected) in inputs {
+            let data = match loader.load(day as u8, &source(day, input)) {
+                Ok(data) => data,
+                Err(e) if e.is_not_found() => {
+                    eprintln!("day {} ({}): skipped, {}", day, input, e);
+                    continue;
+                }
+                Err(e) => panic!("day {} ({}): {}", day, input, e),
+            };
+
+            let results = [
+                check(1, solvers.part_one, &data, &expected.part1),
+                check(2, solvers.part_two, &data, &expected.part2),
+            ];
+            for failure in results.into_iter().flatten() {
+                failures.push(format!("day {} ({}) {}", day, input, failure));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
@@ -0,0 +1,79 @@
+use std::{
+    fmt,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+/// Timings of repeated runs of a single step.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Stats {
+    #[serde(rename = "min_ns", serialize_with = "as_nanos")]
+    pub min: Duration,
+    #[serde(rename = "median_ns", serialize_with = "as_nanos")]
+    pub median: Duration,
+    #[serde(rename = "mean_ns", serialize_with = "as_nanos")]
+    pub mean: Duration,
+}
+
+/// Timings of the parsing and of both parts of a day.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Timings {
+    pub parse: Stats,
+    pub part_one: Stats,
+    pub part_two: Stats,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub day: u8,
+    pub runs: usize,
+    #[serde(flatten)]
+    pub timings: Timings,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Day {} ({} runs)", self.day, self.runs)?;
+        writeln!(f, "{:>8} {:>12} {:>12} {:>12}", "", "min", "median", "mean")?;
+        let steps = [
+            ("parse", &self.timings.parse),
+            ("part 1", &self.timings.part_one),
+            ("part 2", &self.timings.part_two),
+        ];
+        for (step, stats) in steps {
+            writeln!(
+                f,
+                "{:>8} {:>12} {:>12} {:>12}",
+                step,
+                format!("{:.2?}", stats.min),
+                format!("{:.2?}", stats.median),
+                format!("{:.2?}", stats.mean)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs `f` `runs` times and collects the elapsed times.
+pub fn measure<T>(runs: usize, mut f: impl FnMut() -> T) -> Stats {
+    let mut durations: Vec<Duration> = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+    durations.sort();
+
+    Stats {
+        min: durations[0],
+        median: durations[durations.len() / 2],
+        mean: durations.iter().sum::<Duration>() / durations.len() as u32,
+    }
+}
+
+fn as_nanos<S: serde::Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_nanos() as u64)
+}
@@ -1,35 +1,92 @@
-use std::{f64::consts::PI, fmt, ops};
+use std::{cmp::Ordering, f64::consts::PI, fmt, ops};
 
-use num::integer;
+use num::iter;
 
+use crate::scalar::{self, OutOfRange, Scalar};
+
+/// Orthogonal unit steps, clockwise from up.
+const ORTHOGONAL: [(i8, i8); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Orthogonal and diagonal unit steps, clockwise from up.
+const SURROUNDING: [(i8, i8); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// Point or vector on a 2D lattice, in screen space: x grows to the right and y
+/// grows downwards, as in the puzzle maps and screens.
+///
+/// Use [`Coordinates::to_math_space`] and [`Coordinates::from_math_space`] to
+/// convert to and from math space, where y grows upwards.
+///
+/// Components are `i32` unless another signed integer type is picked; use
+/// [`Coordinates::cast`] to convert between them.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
-pub struct Coordinates {
-    x: i32,
-    y: i32,
+pub struct Coordinates<T = i32> {
+    x: T,
+    y: T,
 }
 
-impl fmt::Display for Coordinates {
+impl<T: Scalar> fmt::Display for Coordinates<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f,"({}, {})", self.x, self.y)
+        write!(f, "({}, {})", self.x, self.y)
     }
 }
 
-impl Coordinates {
-    pub fn new(x: i32, y: i32) -> Self {
+impl<T: Scalar> Coordinates<T> {
+    pub fn new(x: T, y: T) -> Self {
         Self { x, y }
     }
 
     pub fn origin() -> Self {
-        Self { x: 0, y: 0 }
+        Self {
+            x: T::zero(),
+            y: T::zero(),
+        }
+    }
+
+    /// Converts math-space components, where y grows upwards, to screen space.
+    pub fn from_math_space(x: T, y: T) -> Self {
+        Self { x, y: -y }
+    }
+
+    /// Same point with y growing upwards, e.g. to measure angles.
+    pub fn to_math_space(&self) -> Coordinates<T> {
+        Self {
+            x: self.x,
+            y: -self.y,
+        }
+    }
+
+    /// Converts the components to another integer type, failing if either of
+    /// them does not fit.
+    pub fn cast<U: Scalar>(&self) -> Result<Coordinates<U>, OutOfRange> {
+        Ok(Coordinates::new(
+            scalar::cast(self.x)?,
+            scalar::cast(self.y)?,
+        ))
     }
 
-    pub fn normalize(&self) -> Coordinates {
-        let gcd = integer::gcd(self.x, self.y);
-        self / gcd
+    /// Smallest lattice vector pointing in the same direction. The null vector
+    /// is returned unchanged.
+    pub fn normalize(&self) -> Coordinates<T> {
+        let gcd = self.x.gcd(&self.y);
+        if gcd.is_zero() { *self } else { self / gcd }
     }
 
+    /// Counter-clockwise angle from the x axis in `[0, 2π)`, taking the
+    /// components as they are. Convert screen-space vectors with
+    /// [`Coordinates::to_math_space`] first to get the usual math angle.
     pub fn angle(&self) -> f64 {
-        let result = (self.y as f64).atan2(self.x as f64);
+        let x = self.x.to_f64().unwrap_or_default();
+        let y = self.y.to_f64().unwrap_or_default();
+        let result = y.atan2(x);
         if result < 0.0 {
             result + 2.0 * PI
         } else {
@@ -37,23 +94,161 @@ impl Coordinates {
         }
     }
 
-    pub fn magnitude(&self) -> i32 {
+    /// Squared euclidean length, which is exact on the lattice.
+    pub fn squared_magnitude(&self) -> T {
         self.x * self.x + self.y * self.y
     }
 
-    pub fn get_x(&self) -> i32 {
+    /// Manhattan (taxicab) length.
+    pub fn manhattan(&self) -> T {
+        self.x.abs() + self.y.abs()
+    }
+
+    pub fn manhattan_distance(&self, other: &Coordinates<T>) -> T {
+        (other - self).manhattan()
+    }
+
+    /// Number of king moves between the two points.
+    pub fn chebyshev_distance(&self, other: &Coordinates<T>) -> T {
+        let diff = other - self;
+        diff.x.abs().max(diff.y.abs())
+    }
+
+    /// Up, right, down and left neighbours.
+    pub fn neighbours_4(&self) -> impl Iterator<Item = Coordinates<T>> {
+        let origin = *self;
+        ORTHOGONAL.iter().map(move |&step| &origin + &unit(step))
+    }
+
+    /// Orthogonal and diagonal neighbours, clockwise from up.
+    pub fn neighbours_8(&self) -> impl Iterator<Item = Coordinates<T>> {
+        let origin = *self;
+        SURROUNDING.iter().map(move |&step| &origin + &unit(step))
+    }
+
+    /// Rotates the vector around the origin by `quarter_turns` steps of 90°,
+    /// clockwise as seen on screen. Negative values turn counter-clockwise.
+    pub fn rotate(&self, quarter_turns: i32) -> Coordinates<T> {
+        match quarter_turns.rem_euclid(4) {
+            0 => *self,
+            1 => Coordinates::new(-self.y, self.x),
+            2 => Coordinates::new(-self.x, -self.y),
+            _ => Coordinates::new(self.y, -self.x),
+        }
+    }
+
+    /// Mirror image across the vertical axis.
+    pub fn reflect_x(&self) -> Coordinates<T> {
+        Coordinates::new(-self.x, self.y)
+    }
+
+    /// Mirror image across the horizontal axis.
+    pub fn reflect_y(&self) -> Coordinates<T> {
+        Coordinates::new(self.x, -self.y)
+    }
+
+    /// Lattice points strictly between `self` and `other` on the segment joining
+    /// them, starting next to `self`. These are the points that can block the line
+    /// of sight between the two.
+    pub fn points_between(&self, other: &Coordinates<T>) -> impl Iterator<Item = Coordinates<T>> {
+        let diff = other - self;
+        let step = diff.normalize();
+        let steps = diff.x.gcd(&diff.y);
+        let origin = *self;
+        iter::range(T::one(), steps).map(move |i| &origin + &(&step * i))
+    }
+
+    pub fn get_x(&self) -> T {
         self.x
     }
 
-    pub fn get_y(&self) -> i32 {
+    pub fn get_y(&self) -> T {
         self.y
     }
 }
 
-impl ops::Div<i32> for &Coordinates {
-    type Output = Coordinates;
+fn unit<T: Scalar>((x, y): (i8, i8)) -> Coordinates<T> {
+    Coordinates::new(T::from(x).unwrap(), T::from(y).unwrap())
+}
 
-    fn div(self, rhs: i32) -> Self::Output {
+/// Reading order: top to bottom, then left to right.
+impl<T: Scalar> Ord for Coordinates<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.y.cmp(&other.y).then(self.x.cmp(&other.x))
+    }
+}
+
+impl<T: Scalar> PartialOrd for Coordinates<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Inclusive rectangle covering a set of coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds<T = i32> {
+    pub min: Coordinates<T>,
+    pub max: Coordinates<T>,
+}
+
+impl<T: Scalar> Bounds<T> {
+    pub fn new(min: Coordinates<T>, max: Coordinates<T>) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_point(point: Coordinates<T>) -> Self {
+        Self::new(point, point)
+    }
+
+    /// Bounding box of the points, or `None` when there are none.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Coordinates<T>>) -> Option<Self>
+    where
+        T: 'a,
+    {
+        let mut points = points.into_iter();
+        let mut bounds = Self::from_point(*points.next()?);
+        points.for_each(|point| bounds.include(point));
+        Some(bounds)
+    }
+
+    /// Grows the bounds so that they contain `point`.
+    pub fn include(&mut self, point: &Coordinates<T>) {
+        self.min = Coordinates::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Coordinates::new(self.max.x.max(point.x), self.max.y.max(point.y));
+    }
+
+    pub fn contains(&self, point: &Coordinates<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    /// Bounds covering the points inside both `self` and `other`, if any.
+    pub fn intersection(&self, other: &Bounds<T>) -> Option<Self> {
+        let min = Coordinates::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y));
+        let max = Coordinates::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y));
+        (min.x <= max.x && min.y <= max.y).then(|| Self::new(min, max))
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x).to_usize().unwrap() + 1
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y).to_usize().unwrap() + 1
+    }
+
+    /// Every point inside the bounds, in reading order.
+    pub fn points(&self) -> impl Iterator<Item = Coordinates<T>> {
+        let (min, max) = (self.min, self.max);
+        iter::range_inclusive(min.y, max.y).flat_map(move |y| {
+            iter::range_inclusive(min.x, max.x).map(move |x| Coordinates::new(x, y))
+        })
+    }
+}
+
+impl<T: Scalar> ops::Div<T> for &Coordinates<T> {
+    type Output = Coordinates<T>;
+
+    fn div(self, rhs: T) -> Self::Output {
         Self::Output {
             x: self.x / rhs,
             y: self.y / rhs,
@@ -61,10 +256,10 @@ impl ops::Div<i32> for &Coordinates {
     }
 }
 
-impl ops::Add<&Coordinates> for &Coordinates {
-    type Output = Coordinates;
+impl<T: Scalar> ops::Add<&Coordinates<T>> for &Coordinates<T> {
+    type Output = Coordinates<T>;
 
-    fn add(self, rhs: &Coordinates) -> Self::Output {
+    fn add(self, rhs: &Coordinates<T>) -> Self::Output {
         Self::Output {
             x: self.x + rhs.x,
             y: self.y + rhs.y,
@@ -72,10 +267,10 @@ impl ops::Add<&Coordinates> for &Coordinates {
     }
 }
 
-impl ops::Mul<i32> for &Coordinates {
-    type Output = Coordinates;
+impl<T: Scalar> ops::Mul<T> for &Coordinates<T> {
+    type Output = Coordinates<T>;
 
-    fn mul(self, rhs: i32) -> Self::Output {
+    fn mul(self, rhs: T) -> Self::Output {
         Self::Output {
             x: rhs * self.x,
             y: rhs * self.y,
@@ -83,13 +278,118 @@ impl ops::Mul<i32> for &Coordinates {
     }
 }
 
-impl ops::Sub<&Coordinates> for &Coordinates {
+impl ops::Mul<&Coordinates> for i32 {
     type Output = Coordinates;
 
-    fn sub(self, rhs: &Coordinates) -> Self::Output {
+    fn mul(self, rhs: &Coordinates) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl<T: Scalar> ops::Sub<&Coordinates<T>> for &Coordinates<T> {
+    type Output = Coordinates<T>;
+
+    fn sub(self, rhs: &Coordinates<T>) -> Self::Output {
         Self::Output {
             x: self.x - rhs.x,
             y: self.y - rhs.y,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_mul() {
+        let c = Coordinates::new(4, 8);
+        let n = c.normalize();
+        let three_times_n = 3 * &n;
+        let half_c = &c / 2;
+
+        assert_eq!(Coordinates::new(1, 2), n);
+        assert_eq!(Coordinates::new(3, 6), three_times_n);
+        assert_eq!(Coordinates::new(2, 4), half_c);
+    }
+
+    #[test]
+    fn test_math_space() {
+        let up = Coordinates::new(0, -1);
+        assert_eq!(Coordinates::new(0, 1), up.to_math_space());
+        assert_eq!(up, Coordinates::from_math_space(0, 1));
+        assert_eq!(std::f64::consts::FRAC_PI_2, up.to_math_space().angle());
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Coordinates::new(1, -2);
+        let b = Coordinates::new(-3, 1);
+        assert_eq!(7, a.manhattan_distance(&b));
+        assert_eq!(4, a.chebyshev_distance(&b));
+        assert_eq!(25, (&b - &a).squared_magnitude());
+    }
+
+    #[test]
+    fn test_rotate() {
+        let right = Coordinates::new(1, 0);
+        assert_eq!(Coordinates::new(0, 1), right.rotate(1));
+        assert_eq!(Coordinates::new(0, -1), right.rotate(-1));
+        assert_eq!(right, right.rotate(4));
+        assert_eq!(Coordinates::new(-1, 0), right.reflect_x());
+    }
+
+    #[test]
+    fn test_points_between() {
+        let points: Vec<Coordinates> = Coordinates::new(0, 0)
+            .points_between(&Coordinates::new(6, -3))
+            .collect();
+        assert_eq!(
+            vec![Coordinates::new(2, -1), Coordinates::new(4, -2)],
+            points
+        );
+        assert_eq!(
+            0,
+            Coordinates::new(0, 0)
+                .points_between(&Coordinates::new(1, 2))
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_reading_order() {
+        let mut points = vec![
+            Coordinates::new(1, 1),
+            Coordinates::new(2, 0),
+            Coordinates::new(0, 1),
+        ];
+        points.sort();
+        assert_eq!(
+            vec![
+                Coordinates::new(2, 0),
+                Coordinates::new(0, 1),
+                Coordinates::new(1, 1)
+            ],
+            points
+        );
+    }
+
+    #[test]
+    fn test_cast() {
+        let big: Coordinates<i64> = Coordinates::new(3, -4);
+        assert_eq!(Ok(Coordinates::new(3, -4)), big.cast::<i32>());
+        assert!(Coordinates::new(i64::MAX, 0).cast::<i32>().is_err());
+    }
+
+    #[test]
+    fn test_bounds_intersection() {
+        let horizontal = Bounds::new(Coordinates::new(0, 2), Coordinates::new(5, 2));
+        let vertical = Bounds::new(Coordinates::new(3, 0), Coordinates::new(3, 4));
+        assert_eq!(
+            Some(Bounds::from_point(Coordinates::new(3, 2))),
+            horizontal.intersection(&vertical)
+        );
+        let apart = Bounds::new(Coordinates::new(6, 0), Coordinates::new(6, 4));
+        assert_eq!(None, horizontal.intersection(&apart));
+    }
+}
@@ -0,0 +1,119 @@
+use num::integer;
+
+/// Cycle reached by iterating a function: the states repeat every `period`
+/// steps once `start` steps have been taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Number of steps after which a state is first repeated.
+    pub fn first_repeat(&self) -> usize {
+        self.start + self.period
+    }
+
+    /// Cycle of a system made of two independent parts cycling as `self` and
+    /// `other`.
+    pub fn combine(&self, other: &Cycle) -> Cycle {
+        Cycle {
+            start: self.start.max(other.start),
+            period: integer::lcm(self.period, other.period),
+        }
+    }
+}
+
+/// Finds the cycle `initial` eventually enters when `step` is applied
+/// repeatedly, using Brent's algorithm.
+pub fn find_cycle<S, F>(initial: &S, step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&mut S),
+{
+    find_cycle_by(initial, step, S::clone)
+}
+
+/// Finds the cycle of the projection `key` of the states reached from
+/// `initial`. The projection must be closed under `step`: two states with the
+/// same key must lead to states with the same key.
+pub fn find_cycle_by<S, K, F, P>(initial: &S, mut step: F, key: P) -> Cycle
+where
+    S: Clone,
+    K: Eq,
+    F: FnMut(&mut S),
+    P: Fn(&S) -> K,
+{
+    // Look for the period, moving the tortoise to the hare at every power of two.
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = key(initial);
+    let mut hare = initial.clone();
+    step(&mut hare);
+    while tortoise != key(&hare) {
+        if power == period {
+            tortoise = key(&hare);
+            power *= 2;
+            period = 0;
+        }
+        step(&mut hare);
+        period += 1;
+    }
+
+    // Walk from the start with a head start of one period until both meet.
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..period {
+        step(&mut hare);
+    }
+    let mut start = 0;
+    while key(&tortoise) != key(&hare) {
+        step(&mut tortoise);
+        step(&mut hare);
+        start += 1;
+    }
+
+    Cycle { start, period }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_find_cycle() {
+        // 0, 1, 2, 3, 4, 5, 6, 3, 4...
+        let step = |x: &mut usize| *x = if *x < 3 { *x + 1 } else { 3 + (*x - 2) % 4 };
+        assert_eq!(
+            Cycle {
+                start: 3,
+                period: 4
+            },
+            find_cycle(&0, step)
+        );
+        assert_eq!(
+            Cycle {
+                start: 0,
+                period: 4
+            },
+            find_cycle(&5, step)
+        );
+    }
+
+    #[test]
+    fn test_find_cycle_by() {
+        let step = |state: &mut (u32, u32)| *state = ((state.0 + 1) % 3, state.1 + 1);
+        let cycle = find_cycle_by(&(0, 0), step, |state| state.0);
+        assert_eq!(3, cycle.period);
+        assert_eq!(
+            Cycle {
+                start: 2,
+                period: 12
+            },
+            cycle.combine(&Cycle {
+                start: 2,
+                period: 4
+            })
+        );
+    }
+}
@@ -1,13 +1,14 @@
-pub fn solve_part_one(test: bool) {
-    let weights = load_data(test);
-    let fuel = weights.into_iter().map(get_required_fuel).sum::<u32>();
-    println!("Part 1 - Fuel needed: {}", fuel);
+use aoc2019::{Input, Params};
+
+pub fn solve_part_one(weights: &[u32], _params: &Params) -> Result<String, String> {
+    let fuel = weights.iter().copied().map(get_required_fuel).sum::<u32>();
+    Ok(fuel.to_string())
 }
 
-pub fn solve_part_two(test: bool) {
-    let weights = load_data(test);
+pub fn solve_part_two(weights: &[u32], _params: &Params) -> Result<String, String> {
     let fuel = weights
-        .into_iter()
+        .iter()
+        .copied()
         .map(|w| {
             let mut total_fuel = get_required_fuel(w);
             let mut fuel = get_required_fuel(total_fuel);
@@ -18,16 +19,20 @@ pub fn solve_part_two(test: bool) {
             total_fuel
         })
         .sum::<u32>();
-    println!("Part 2 - Fuel needed: {}", fuel);
+    Ok(fuel.to_string())
 }
 
 fn get_required_fuel(weight: u32) -> u32 {
     if weight < 9 { 0 } else { weight / 3 - 2 }
 }
 
-fn load_data(test: bool) -> Vec<u32> {
-    aoc2019::load_data(1, test)
+pub fn parse(input: &Input) -> Result<Vec<u32>, String> {
+    input
+        .data()
         .lines()
-        .map(|line| line.parse::<u32>().expect("Oops..."))
+        .map(|line| {
+            line.parse::<u32>()
+                .map_err(|e| format!("invalid weight {}: {}", line, e))
+        })
         .collect()
 }
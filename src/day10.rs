@@ -1,232 +1,383 @@
 use std::{
-    collections::HashSet, f64::consts::{FRAC_PI_2, PI}, fmt::Display, ops::{Add, Div, Mul, Sub}
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap},
+    fmt::{Display, Write},
+    str::FromStr,
 };
 
-use num::integer;
+use aoc2019::{Input, Params, coordinates::Coordinates, grid::Grid};
 
-pub fn solve_part_one(test: bool) {
-    let field = load_data(test);
-    let best_position = field.best_position().1;
-    println!("Part 1: {}", best_position);
+pub fn solve_part_one(field: &AsteroidField, _params: &Params) -> Result<String, String> {
+    Ok(field.best_position()?.1.to_string())
 }
 
-pub fn solve_part_two(test: bool) {
-    let field = load_data(test);
-    let best_position = field.best_position().0;
-    let nth_position = field.vaporize(&best_position, 200);
-    println!("Part 2: {}", nth_position.x * 100 + nth_position.y);
+/// Finds the `nth` asteroid vaporised by the laser of the best station, which
+/// starts pointing up and turns clockwise unless the `start=x,y` and `rotation`
+/// parameters say otherwise.
+pub fn solve_part_two(field: &AsteroidField, params: &Params) -> Result<String, String> {
+    let station = field.best_position()?.0;
+    let laser = laser(params)?;
+    let nth: usize = params.get_or("nth", 200)?;
+
+    let asteroid = nth
+        .checked_sub(1)
+        .and_then(|index| field.vaporisation_order(&station, &laser).nth(index));
+    asteroid
+        .map(|asteroid| (asteroid.get_x() * 100 + asteroid.get_y()).to_string())
+        .ok_or_else(|| String::from("not that many asteroids to vaporise"))
 }
 
-struct AsteroidField {
-    height: usize,
-    width: usize,
-    asteroids: HashSet<Coordinates>,
-}
-
-impl Display for AsteroidField {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if self.asteroids.contains(&Coordinates {
-                    x: x as i32,
-                    y: y as i32,
-                }) {
-                    write!(f, "#")?;
-                } else {
-                    write!(f, ".")?;
-                }
-            }
-            writeln!(f)?;
+/// `heatmap=true` shows how many asteroids each one sees, `visible=true` what
+/// the best station sees, `blocker=x,y` the asteroid hiding `x,y` from the
+/// station, if any, and `order=true` every asteroid in the order the laser
+/// vaporises them.
+pub fn report(field: &AsteroidField, params: &Params) -> Result<String, String> {
+    let visibility = field.visibility();
+    let (station, _) = best(&visibility).ok_or(NO_ASTEROID)?;
+    let mut report = String::new();
+
+    if params.get_or("heatmap", false)? {
+        _ = writeln!(report, "{}", heat_map(&visibility));
+    }
+    if params.get_or("visible", false)? {
+        _ = writeln!(report, "{}", field.sight_map(&station));
+    }
+    if let Some(Point(target)) = params.get("blocker")? {
+        _ = match field.blocker(&station, &target) {
+            Some(blocker) => writeln!(report, "{} hides {} from {}", blocker, target, station),
+            None => writeln!(
+                report,
+                "{} has a clear line of sight to {}",
+                station, target
+            ),
+        };
+    }
+    if params.get_or("order", false)? {
+        let order = field.vaporisation_order(&station, &laser(params)?);
+        for (index, asteroid) in order.enumerate() {
+            _ = writeln!(report, "{}: {}", index + 1, asteroid);
         }
-        Ok(())
     }
-}
 
-impl AsteroidField {
-    fn best_position(&self) -> (Coordinates, usize) {
-        let mut best = 0;
-        let mut best_position = Coordinates::new(0, 0);
-
-        for source in self.asteroids.iter() {
-            let count = self.count_reachable_aseroids(source);
-            if count > best {
-                best = count;
-                best_position = *source;
-            }
-        }
+    Ok(report)
+}
 
-        (best_position, best)
+/// Laser set by the `start=x,y` and `rotation` parameters.
+fn laser(params: &Params) -> Result<Laser, String> {
+    let default = Laser::default();
+    let laser = Laser {
+        start: params
+            .get("start")?
+            .map_or(default.start, |Point(start)| start),
+        rotation: params.get_or("rotation", default.rotation)?,
+    };
+    if laser.start == Coordinates::origin() {
+        return Err(String::from("the laser must point somewhere"));
     }
+    Ok(laser)
+}
 
-    fn count_reachable_aseroids(&self, source: &Coordinates) -> usize {
-        let mut normalized_diffs = HashSet::new();
+/// Way the laser turns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    Clockwise,
+    Counterclockwise,
+}
 
-        for asteroid in self.asteroids.iter() {
-            if asteroid == source {
-                continue;
-            }
+impl FromStr for Rotation {
+    type Err = String;
 
-            let normalized_diff = (asteroid - source).normalize();
-            normalized_diffs.insert(normalized_diff);
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clockwise" => Ok(Self::Clockwise),
+            "counterclockwise" => Ok(Self::Counterclockwise),
+            _ => Err(format!("Unknown rotation {}", s)),
         }
-
-        normalized_diffs.len()
     }
+}
 
-    fn vaporize(&self, base_position: &Coordinates, count: usize) -> Coordinates {
-        let mut list = Vec::new();
-        for asteroid in self.asteroids.iter() {
-            if asteroid == base_position {
-                continue;
-            }
+/// Laser of the monitoring station, sweeping from the `start` direction.
+pub struct Laser {
+    pub start: Coordinates,
+    pub rotation: Rotation,
+}
 
-            let diff = asteroid - base_position;
-            list.push((diff.angle(), diff.magnitude(), *asteroid));
+impl Default for Laser {
+    /// Laser pointing up and turning clockwise, as in the puzzle.
+    fn default() -> Self {
+        Self {
+            start: Coordinates::new(0, -1),
+            rotation: Rotation::Clockwise,
         }
+    }
+}
 
-        list.sort_by(|a, b| {
-            a.0.partial_cmp(&b.0).unwrap().reverse().then(a.1.cmp(&b.1))
-        });
+impl Laser {
+    /// Orders directions by the angle the laser turns from `start` to reach
+    /// them, using exact integer arithmetic.
+    pub fn compare(&self, a: &Coordinates, b: &Coordinates) -> Ordering {
+        self.half_turn(a)
+            .cmp(&self.half_turn(b))
+            .then_with(|| self.cross(b, a).cmp(&0))
+    }
 
-        let mut removed = Vec::with_capacity(count);
-        for (idx, v) in list.iter().enumerate() {
-            if v.0 <= FRAC_PI_2 {
-                removed.push(list.remove(idx));
-                break;
-            }
-        }
-        if removed.last().is_none() {
-            removed.push(list.remove(0));
-        }
+    /// 0 for directions less than half a turn away from `start`, 1 for the
+    /// others. Within a half turn, the cross product orders directions.
+    fn half_turn(&self, direction: &Coordinates) -> u8 {
+        let cross = self.cross(&self.start, direction);
+        let dot = i64::from(self.start.get_x()) * i64::from(direction.get_x())
+            + i64::from(self.start.get_y()) * i64::from(direction.get_y());
+        u8::from(cross < 0 || (cross == 0 && dot < 0))
+    }
 
-        'outer: for _ in 1..count {
-            for (idx, v) in list.iter().enumerate() {
-                if v.0 < removed.last().unwrap().0 {
-                    removed.push(list.remove(idx));
-                    continue 'outer;
-                }
-            }
-            removed.push(list.remove(0));
+    /// Cross product of `a` and `b`, positive when the laser turns less than
+    /// half a turn from `a` to `b`.
+    fn cross(&self, a: &Coordinates, b: &Coordinates) -> i64 {
+        // In screen space, y grows downwards: a positive cross product turns
+        // clockwise.
+        let cross = i64::from(a.get_x()) * i64::from(b.get_y())
+            - i64::from(a.get_y()) * i64::from(b.get_x());
+        match self.rotation {
+            Rotation::Clockwise => cross,
+            Rotation::Counterclockwise => -cross,
         }
-
-        removed.last().unwrap().2
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-struct Coordinates {
-    x: i32,
-    y: i32,
+pub struct AsteroidField {
+    map: Grid<bool>,
+    asteroids: BTreeSet<Coordinates>,
 }
 
-impl Add<&Coordinates> for &Coordinates {
-    type Output = Coordinates;
-
-    fn add(self, rhs: &Coordinates) -> Self::Output {
-        Self::Output {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
+impl Display for AsteroidField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let map = self
+            .map
+            .display(|&asteroid| if asteroid { '#' } else { '.' });
+        writeln!(f, "{}", map)
     }
 }
 
-impl Sub<&Coordinates> for &Coordinates {
-    type Output = Coordinates;
-
-    fn sub(self, rhs: &Coordinates) -> Self::Output {
-        Self::Output {
-            x: self.x - rhs.x,
-            y: rhs.y - self.y,
-        }
+impl AsteroidField {
+    fn best_position(&self) -> Result<(Coordinates, usize), String> {
+        best(&self.visibility()).ok_or_else(|| String::from(NO_ASTEROID))
     }
-}
 
-impl Mul<&Coordinates> for i32 {
-    type Output = Coordinates;
+    /// Number of asteroids each asteroid sees, `None` on empty cells.
+    pub fn visibility(&self) -> Grid<Option<usize>> {
+        let cells = self
+            .map
+            .iter()
+            .map(|(position, &asteroid)| asteroid.then(|| self.visible_from(&position).len()))
+            .collect();
 
-    fn mul(self, rhs: &Coordinates) -> Self::Output {
-        Self::Output {
-            x: self * rhs.x,
-            y: self * rhs.y,
-        }
+        Grid::from_vec(self.map.width(), self.map.height(), cells)
     }
-}
 
-impl Div<i32> for &Coordinates {
-    type Output = Coordinates;
-
-    fn div(self, rhs: i32) -> Self::Output {
-        Self::Output {
-            x: self.x / rhs,
-            y: self.y / rhs,
+    /// Asteroids in direct line of sight of `station`: the closest one in each
+    /// direction.
+    pub fn visible_from(&self, station: &Coordinates) -> BTreeSet<Coordinates> {
+        let mut closest: HashMap<Coordinates, Coordinates> = HashMap::new();
+
+        for asteroid in self
+            .asteroids
+            .iter()
+            .filter(|&asteroid| asteroid != station)
+        {
+            let offset = asteroid - station;
+            closest
+                .entry(offset.normalize())
+                .and_modify(|current| {
+                    if offset.manhattan() < (&*current - station).manhattan() {
+                        *current = *asteroid;
+                    }
+                })
+                .or_insert(*asteroid);
         }
+
+        closest.into_values().collect()
     }
-}
 
-impl Coordinates {
-    fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
+    /// First asteroid on the way from `from` to `to`, hiding one from the other.
+    pub fn blocker(&self, from: &Coordinates, to: &Coordinates) -> Option<Coordinates> {
+        from.points_between(to)
+            .find(|point| self.asteroids.contains(point))
     }
 
-    fn normalize(&self) -> Coordinates {
-        let gcd = integer::gcd(self.x, self.y);
-        self / gcd
+    /// Map of the field from `station`, drawn with `O`, asteroids it sees
+    /// with `*` and hidden ones with `#`.
+    fn sight_map(&self, station: &Coordinates) -> String {
+        let visible = self.visible_from(station);
+        let cells = self
+            .map
+            .iter()
+            .map(|(position, &asteroid)| match asteroid {
+                _ if position == *station => 'O',
+                true if visible.contains(&position) => '*',
+                true => '#',
+                false => '.',
+            })
+            .collect();
+
+        Grid::from_vec(self.map.width(), self.map.height(), cells)
+            .display(|&c| c)
+            .to_string()
     }
 
-    fn angle(&self) -> f64 {
-        let result = (self.y as f64).atan2(self.x as f64);
-        if result < 0.0 {
-            result + 2.0 * PI
-        } else {
-            result
+    /// Asteroids in the order the laser of `station` vaporises them: one per
+    /// direction on each turn, the closest first.
+    pub fn vaporisation_order(
+        &self,
+        station: &Coordinates,
+        laser: &Laser,
+    ) -> impl Iterator<Item = Coordinates> + use<> {
+        assert_ne!(
+            laser.start,
+            Coordinates::origin(),
+            "The laser must point somewhere"
+        );
+
+        let mut directions: HashMap<Coordinates, Vec<Coordinates>> = HashMap::new();
+        for asteroid in self
+            .asteroids
+            .iter()
+            .filter(|&asteroid| asteroid != station)
+        {
+            directions
+                .entry((asteroid - station).normalize())
+                .or_default()
+                .push(*asteroid);
         }
-    }
 
-    fn magnitude(&self) -> i32 {
-        self.x * self.x + self.y * self.y
+        let mut directions: Vec<_> = directions.into_iter().collect();
+        directions.sort_by(|(a, _), (b, _)| laser.compare(a, b));
+
+        // The asteroid at distance rank `turn` in its direction goes on that
+        // turn, after those of the directions the laser reaches first.
+        let mut order = Vec::with_capacity(self.asteroids.len());
+        for (index, (_, asteroids)) in directions.iter_mut().enumerate() {
+            asteroids.sort_by_key(|asteroid| (asteroid - station).manhattan());
+            order.extend(
+                asteroids
+                    .iter()
+                    .enumerate()
+                    .map(|(turn, &asteroid)| (turn, index, asteroid)),
+            );
+        }
+        order.sort_unstable();
+
+        order.into_iter().map(|(_, _, asteroid)| asteroid)
     }
 }
 
-fn load_data(test: bool) -> AsteroidField {
-    let mut height = 0;
-    let mut width = 0;
+/// Error returned when there is no asteroid to build the station on.
+const NO_ASTEROID: &str = "no asteroid in the field";
+
+/// Asteroid seeing the most others, first in reading order on ties, with the
+/// number it sees, or `None` if there are no asteroids.
+fn best(visibility: &Grid<Option<usize>>) -> Option<(Coordinates, usize)> {
+    visibility
+        .iter()
+        .filter_map(|(position, count)| count.map(|count| (position, count)))
+        .fold(
+            None,
+            |best: Option<(Coordinates, usize)>, (position, count)| match best {
+                Some((_, most)) if most >= count => best,
+                _ => Some((position, count)),
+            },
+        )
+}
 
-    let asteroids = aoc2019::load_data(10, test)
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            height += 1;
-            width = line.len();
-            line.chars().enumerate().filter_map(move |(x, c)| {
-                if c == '#' {
-                    Some(Coordinates::new(x as i32, y as i32))
-                } else {
-                    None
-                }
-            })
+/// Visibility counts scaled to digits, from `0` for the asteroids seeing the
+/// fewest others to `9` for the best station.
+fn heat_map(visibility: &Grid<Option<usize>>) -> String {
+    let counts = || visibility.cells().iter().flatten().copied();
+    let least = counts().min().unwrap_or(0);
+    let range = (counts().max().unwrap_or(0) - least).max(1);
+
+    visibility
+        .display(|count| match count {
+            Some(count) => {
+                char::from_digit(((count - least) * 9 / range) as u32, 10).expect("Oops...")
+            }
+            None => '.',
         })
-        .collect();
+        .to_string()
+}
 
-    AsteroidField {
-        height,
-        width,
-        asteroids,
+/// Position or direction given as `x,y`.
+struct Point(Coordinates);
+
+impl FromStr for Point {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Invalid position {}", s);
+        let (x, y) = s.split_once(',').ok_or_else(error)?;
+        let x = x.trim().parse().map_err(|_| error())?;
+        let y = y.trim().parse().map_err(|_| error())?;
+        Ok(Self(Coordinates::new(x, y)))
     }
 }
 
+pub fn parse(input: &Input) -> Result<AsteroidField, String> {
+    let map = Grid::parse(input.data(), |c| c == '#')
+        .map_err(|e| format!("invalid asteroid map: {}", e))?;
+
+    let asteroids = map
+        .iter()
+        .filter(|(_, asteroid)| **asteroid)
+        .map(|(position, _)| position)
+        .collect();
+
+    Ok(AsteroidField { map, asteroids })
+}
+
 #[cfg(test)]
 mod test {
-    use super::Coordinates;
+    use super::*;
+
+    const EXAMPLE: &str = ".#..#\n.....\n#####\n....#\n...##\n";
+
+    fn example() -> AsteroidField {
+        parse(&Input::new(String::from(EXAMPLE), Params::default())).unwrap()
+    }
+
+    #[test]
+    fn test_visibility() {
+        let field = example();
+        let visibility = field.visibility();
+        let counts = visibility.display(|count| {
+            count.map_or('.', |count| char::from_digit(count as u32, 10).unwrap())
+        });
+        assert_eq!(".7..7\n.....\n67775\n....7\n...87", counts.to_string());
+        assert_eq!(Some((Coordinates::new(3, 4), 8)), best(&visibility));
+        assert_eq!(".6..6\n.....\n36660\n....6\n...96", heat_map(&visibility));
+    }
+
+    #[test]
+    fn test_line_of_sight() {
+        let field = example();
+        let station = Coordinates::new(3, 4);
+        let visible = field.visible_from(&station);
+        assert_eq!(8, visible.len());
+        assert!(!visible.contains(&Coordinates::new(1, 0)));
+        assert!(visible.contains(&Coordinates::new(2, 2)));
+
+        assert_eq!(
+            Some(Coordinates::new(2, 2)),
+            field.blocker(&station, &Coordinates::new(1, 0))
+        );
+        assert_eq!(None, field.blocker(&station, &Coordinates::new(4, 0)));
+    }
 
     #[test]
-    fn test_mul() {
-        let c = Coordinates::new(4, 8);
-        let n = c.normalize();
-        let three_times_n = 3 * &n;
-        let half_c = &c / 2;
-
-        assert_eq!(Coordinates { x: 1, y: 2 }, n);
-        assert_eq!(Coordinates { x: 3, y: 6 }, three_times_n);
-        assert_eq!(Coordinates { x: 2, y: 4 }, half_c);
+    fn test_no_asteroid() {
+        let params = Params::default();
+        let field = parse(&Input::new(String::from(".....\n"), params.clone())).unwrap();
+        let error = Err(String::from(NO_ASTEROID));
+        assert_eq!(error, solve_part_one(&field, &params));
+        assert_eq!(error, solve_part_two(&field, &params));
+        assert_eq!(error, report(&field, &params));
     }
 }
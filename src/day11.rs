@@ -1,25 +1,27 @@
-use std::{collections::HashMap, fmt::Display};
+use std::fmt::Display;
 
-use crate::{coordinates::Coordinates, direction::Direction, intcode::Intcode};
+use aoc2019::{
+    Input, Params, coordinates::Coordinates, direction::Direction, grid::SparseGrid,
+    intcode::Intcode, ocr,
+};
 
-pub fn solve_part_one(test: bool) {
-    let program = load_data(test);
-    let mut robot = Robot::new(program);
+pub fn solve_part_one(program: &Intcode, _params: &Params) -> Result<String, String> {
+    let mut robot = Robot::new(program.clone());
     let mut hull = Hull::new();
 
     robot.paint(&mut hull);
-    println!("Part 1: {}", hull.surface());
+    Ok(hull.surface().to_string())
 }
 
-pub fn solve_part_two(test: bool) {
-    let program = load_data(test);
-    let mut robot = Robot::new(program);
+pub fn solve_part_two(program: &Intcode, _params: &Params) -> Result<String, String> {
+    let mut robot = Robot::new(program.clone());
     let mut hull = Hull::new();
     hull.set(&Coordinates::default(), 1);
 
     robot.paint(&mut hull);
-    println!("Part 2:");
-    println!("{}", hull);
+    // Fall back to the picture when it does not spell letters.
+    Ok(ocr::read_sparse(&hull.hull, |&color| color == 1)
+        .unwrap_or_else(|_| hull.to_string().trim_end().to_owned()))
 }
 
 struct Robot {
@@ -59,13 +61,13 @@ impl Robot {
 }
 
 struct Hull {
-    hull: HashMap<Coordinates, i64>,
+    hull: SparseGrid<i64>,
 }
 
 impl Hull {
     fn new() -> Self {
         Hull {
-            hull: HashMap::new(),
+            hull: SparseGrid::new(),
         }
     }
 
@@ -84,23 +86,13 @@ impl Hull {
 
 impl Display for Hull {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let x_min = self.hull.keys().map(|c| c.get_x()).min().unwrap();
-        let x_max = self.hull.keys().map(|c| c.get_x()).max().unwrap();
-        let y_min = self.hull.keys().map(|c| c.get_y()).min().unwrap();
-        let y_max = self.hull.keys().map(|c| c.get_y()).max().unwrap();
-
-        for y in (y_min..=y_max).rev() {
-            for x in x_min..=x_max {
-                let color = self.get(&Coordinates::new(x, y));
-                write!(f, "{}", if color == 1 { '#' } else { ' ' })?;
-            }
-            writeln!(f)?;
-        }
-
-        Ok(())
+        let hull = self
+            .hull
+            .display(|color| if color == Some(&1) { '#' } else { ' ' });
+        writeln!(f, "{}", hull)
     }
 }
 
-fn load_data(test: bool) -> Intcode {
-    Intcode::from(aoc2019::load_data(11, test).trim())
-}
\ No newline at end of file
+pub fn parse(input: &Input) -> Result<Intcode, String> {
+    Ok(Intcode::from(input.data().trim()))
+}
@@ -1,97 +1,43 @@
-use num::integer;
-
-use crate::vec3d::Vec3D;
-
-pub fn solve_part_one(test: bool) {
-    let mut positions = load_data(test);
-    let mut velocities: Vec<Vec3D> = (0..positions.len()).map(|_| Vec3D::default()).collect();
-
-    let limit = if test { 10 } else { 1000 };
-    for _ in 0..limit {
-        simulate_motion(&mut positions, &mut velocities);
-    }
-
-    let total_energy: i64 = (0..positions.len())
-        .map(|i| positions[i].energy() * velocities[i].energy())
-        .sum();
-    println!("Part 1: {}", total_energy);
-}
-
-pub fn solve_part_two(test: bool) {
-    let mut positions = load_data(test);
-    let mut velocities: Vec<Vec3D> = (0..positions.len()).map(|_| Vec3D::default()).collect();
-
-    let origins = positions.clone();
-    let mut x_cycle = 0;
-    let mut y_cycle = 0;
-    let mut z_cycle = 0;
-
-    let mut cycle: usize = 0;
-    while x_cycle == 0 || y_cycle == 0 || z_cycle == 0 {
-        simulate_motion(&mut positions, &mut velocities);
-        cycle += 1;
-
-        if positions
-            .iter()
-            .enumerate()
-            .all(|(idx, p)| p.x == origins[idx].x)
-            && velocities.iter().all(|v| v.x == 0)
-            && x_cycle == 0
-        {
-            x_cycle = cycle;
-        }
-        if positions
-            .iter()
-            .enumerate()
-            .all(|(idx, p)| p.y == origins[idx].y)
-            && velocities.iter().all(|v| v.y == 0)
-            && y_cycle == 0
-        {
-            y_cycle = cycle;
-        }
-        if positions
-            .iter()
-            .enumerate()
-            .all(|(idx, p)| p.z == origins[idx].z)
-            && velocities.iter().all(|v| v.z == 0)
-            && z_cycle == 0
-        {
-            z_cycle = cycle;
-        }
-    }
-
-    let mut lcm = integer::lcm(x_cycle, y_cycle);
-    lcm = integer::lcm(lcm, z_cycle);
-    println!("Part 2: {}", lcm);
+use aoc2019::{
+    Input, Params,
+    cycle::{Cycle, find_cycle_by},
+    nbody::MoonSystem,
+    vector::{ParseVecError, Vec3D},
+};
+
+pub fn solve_part_one(positions: &[Vec3D], params: &Params) -> Result<String, String> {
+    let mut system = MoonSystem::new(positions);
+    system.run(params.get_or("steps", 1000)?);
+    Ok(system.energy().to_string())
 }
 
-fn simulate_motion(moons: &mut [Vec3D], velocities: &mut [Vec3D]) {
-    apply_gravity(moons, velocities);
-    apply_velocity(moons, velocities);
+pub fn solve_part_two(positions: &[Vec3D], _params: &Params) -> Result<String, String> {
+    let system = MoonSystem::new(positions);
+    Ok(cycle(&system).first_repeat().to_string())
 }
 
-fn apply_gravity(moons: &[Vec3D], velocities: &mut [Vec3D]) {
-    for (i, moon) in moons.iter().enumerate() {
-        for (j, other) in moons.iter().enumerate() {
-            if i == j {
-                continue;
-            } else {
-                let gravity = (other - moon).signum();
-                velocities[i] += gravity;
-            }
-        }
-    }
+/// Cycle of the whole system, combined from the cycles of its axes as they
+/// evolve independently under the puzzle's gravity.
+fn cycle<const N: usize>(system: &MoonSystem<N>) -> Cycle {
+    (0..N)
+        .map(|axis| find_cycle_by(system, MoonSystem::step, |system| axis_state(system, axis)))
+        .reduce(|a, b| a.combine(&b))
+        .expect("Oops...")
 }
 
-fn apply_velocity(moons: &mut [Vec3D], velocities: &[Vec3D]) {
-    for (i, moon) in moons.iter_mut().enumerate() {
-        *moon += velocities[i];
-    }
+fn axis_state<const N: usize>(system: &MoonSystem<N>, axis: usize) -> Vec<(i64, i64)> {
+    system
+        .bodies()
+        .iter()
+        .map(|body| (body.position[axis], body.velocity[axis]))
+        .collect()
 }
 
-fn load_data(test: bool) -> Vec<Vec3D> {
-    aoc2019::load_data(12, test)
+pub fn parse(input: &Input) -> Result<Vec<Vec3D>, String> {
+    input
+        .data()
         .lines()
-        .map(Vec3D::from)
-        .collect()
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .map_err(|e: ParseVecError| e.to_string())
 }
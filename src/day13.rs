@@ -1,9 +1,9 @@
-use std::{cmp::Ordering, collections::HashMap, fmt};
+use std::{cmp::Ordering, fmt};
 
-use crate::{coordinates::Coordinates, intcode::Intcode};
+use aoc2019::{Input, Params, coordinates::Coordinates, grid::SparseGrid, intcode::Intcode};
 
-pub fn solve_part_one(test: bool) {
-    let mut program = load_data(test);
+pub fn solve_part_one(program: &Intcode, _params: &Params) -> Result<String, String> {
+    let mut program = program.clone();
     let mut screen = Screen::new();
     screen.fill(&mut program);
     let block_count = screen
@@ -11,51 +11,39 @@ pub fn solve_part_one(test: bool) {
         .values()
         .filter(|tile| matches!(tile, Tile::Block))
         .count();
-    println!("Part 1: {}", block_count);
+    Ok(block_count.to_string())
 }
 
-pub fn solve_part_two(test: bool) {
-    let mut program = load_data(test);
+pub fn solve_part_two(program: &Intcode, _params: &Params) -> Result<String, String> {
+    let mut program = program.clone();
     program[0] = 2;
     let mut screen = Screen::new();
     let score = screen.fill(&mut program);
-    println!("Part 2: {}", score);
+    Ok(score.to_string())
 }
 
 struct Screen {
-    tiles: HashMap<Coordinates, Tile>,
+    tiles: SparseGrid<Tile>,
 }
 
 impl fmt::Display for Screen {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let x_max = self.tiles.keys().map(|c| c.get_x()).max().unwrap();
-        let y_max = self.tiles.keys().map(|c| c.get_y()).max().unwrap();
-
-        for y in 0..=y_max {
-            for x in 0..=x_max {
-                if let Some(tile) = self.tiles.get(&Coordinates::new(x, y)) {
-                    match tile {
-                        Tile::Empty => write!(f, " ")?,
-                        Tile::Wall => write!(f, "*")?,
-                        Tile::Block => write!(f, "#")?,
-                        Tile::Paddle => write!(f, "_")?,
-                        Tile::Ball => write!(f, ".")?,
-                    }
-                } else {
-                    write!(f, "?")?;
-                }
-            }
-            writeln!(f)?;
-        }
-
-        Ok(())
+        let screen = self.tiles.display(|tile| match tile {
+            Some(Tile::Empty) => ' ',
+            Some(Tile::Wall) => '*',
+            Some(Tile::Block) => '#',
+            Some(Tile::Paddle) => '_',
+            Some(Tile::Ball) => '.',
+            None => '?',
+        });
+        writeln!(f, "{}", screen)
     }
 }
 
 impl Screen {
     fn new() -> Self {
         Self {
-            tiles: HashMap::new(),
+            tiles: SparseGrid::new(),
         }
     }
 
@@ -81,7 +69,9 @@ impl Screen {
                     let y = program.next_output().expect("No corresponding y output");
                     let tile = program.next_output().expect("No corresponding tile output");
 
-                    let pixel = Coordinates::new(x as i32, y as i32);
+                    let pixel = Coordinates::new(x, y)
+                        .cast()
+                        .unwrap_or_else(|e| panic!("Tile position out of range: {}", e));
                     let tile = Tile::from(tile);
 
                     match tile {
@@ -128,6 +118,6 @@ impl From<i64> for Tile {
     }
 }
 
-fn load_data(test: bool) -> Intcode {
-    Intcode::from(aoc2019::load_data(13, test).trim())
+pub fn parse(input: &Input) -> Result<Intcode, String> {
+    Ok(Intcode::from(input.data().trim()))
 }
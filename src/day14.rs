@@ -2,25 +2,25 @@ use std::{collections::HashMap, ops};
 
 use regex::Regex;
 
-pub fn solve_part_one(test: bool) {
-    let reactions = load_data(test);
-    let ore = fuel_cost(&reactions, 1);
-    println!("Part 1: {}", ore);
+use aoc2019::{Input, Params};
+
+pub fn solve_part_one(reactions: &Reactions, _params: &Params) -> Result<String, String> {
+    let ore = fuel_cost(reactions, 1);
+    Ok(ore.to_string())
 }
 
-pub fn solve_part_two(test: bool) {
-    let reactions = load_data(test);
-    let max_fuel = max_fuel(&reactions, 1_000_000_000_000);
-    println!("Part 2: {}", max_fuel);
+pub fn solve_part_two(reactions: &Reactions, _params: &Params) -> Result<String, String> {
+    let max_fuel = max_fuel(reactions, 1_000_000_000_000);
+    Ok(max_fuel.to_string())
 }
 
 #[derive(Debug)]
-struct Reactions {
+pub struct Reactions {
     reactions: HashMap<String, Reaction>,
 }
 
-impl From<String> for Reactions {
-    fn from(value: String) -> Self {
+impl From<&str> for Reactions {
+    fn from(value: &str) -> Self {
         let reactions = value
             .lines()
             .map(|line| {
@@ -55,10 +55,9 @@ fn fuel_cost(reactions: &Reactions, fuel_amount: u64) -> u64 {
 
                 if let Some(result) =
                     (reaction.produced.quantity * reaction_multiplier).checked_sub(remaining)
+                    && result > 0
                 {
-                    if result > 0 {
-                        *leftovers.entry(required.element).or_insert(0) += result;
-                    }
+                    *leftovers.entry(required.element).or_insert(0) += result;
                 }
 
                 for requirement in &reaction.required {
@@ -174,6 +173,6 @@ impl Ingredient {
     }
 }
 
-fn load_data(test: bool) -> Reactions {
-    Reactions::from(aoc2019::load_data(14, test))
+pub fn parse(input: &Input) -> Result<Reactions, String> {
+    Ok(Reactions::from(input.data()))
 }
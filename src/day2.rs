@@ -1,15 +1,15 @@
-use crate::intcode::Intcode;
+use aoc2019::{Input, Params, intcode::Intcode};
 
-pub fn solve_part_one(test: bool) {
-    let mut program = load_data(test);
+pub fn solve_part_one(program: &Intcode, _params: &Params) -> Result<String, String> {
+    let mut program = program.clone();
 
     program.execute();
 
-    println!("Part 1: {}", program[0]);
+    Ok(program[0].to_string())
 }
 
-pub fn solve_part_two(test: bool) {
-    let initial_program = load_data(test);
+pub fn solve_part_two(initial_program: &Intcode, params: &Params) -> Result<String, String> {
+    let target = params.get_or("target", 19690720)?;
 
     let mut noun = 0;
     let mut verb = 0;
@@ -21,24 +21,26 @@ pub fn solve_part_two(test: bool) {
             program[2] = verb;
             program.execute();
 
-            if program[0] == 19690720 {
-                println!("Part 2: {}", 100 * noun + verb);
-                return;
+            if program[0] == target {
+                return Ok((100 * noun + verb).to_string());
             }
             verb += 1;
         }
         noun += 1;
         verb = 0;
     }
+
+    Err(String::from("no noun and verb produce the expected output"))
 }
 
-fn load_data(test: bool) -> Intcode {
-    let mut program = Intcode::from(aoc2019::load_data(2, test).trim_end());
+pub fn parse(input: &Input) -> Result<Intcode, String> {
+    let mut program = Intcode::from(input.data().trim_end());
 
-    if !test {
-        program[1] = 12;
-        program[2] = 2;
+    let params = input.params();
+    if params.get_or("patch", true)? {
+        program[1] = params.get_or("noun", 12)?;
+        program[2] = params.get_or("verb", 2)?;
     }
 
-    program
+    Ok(program)
 }
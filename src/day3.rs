@@ -1,156 +1,377 @@
-use std::collections::HashSet;
+use std::{collections::BTreeSet, fmt::Write, fs, iter};
 
+use itertools::Itertools;
 use nom::{
-    IResult, Parser, branch::alt, bytes::complete::tag, character::complete::digit1,
-    combinator::map, multi::separated_list1,
+    IResult, Parser,
+    bytes::complete::tag,
+    character::complete::{digit1, one_of},
+    combinator::{all_consuming, map, map_opt},
+    multi::separated_list1,
 };
 
-pub fn solve_part_one(test: bool) {
-    let wires = load_data(test);
-    let closest_intersection_distance: i32 = wires[0]
-        .intersections(&wires[1])
+use aoc2019::{
+    Input, Params,
+    coordinates::{Bounds, Coordinates},
+    direction::Direction,
+    grid::SparseGrid,
+};
+
+pub fn solve_part_one(wires: &[Wire], params: &Params) -> Result<String, String> {
+    let closest_intersection_distance = intersections(wires, params)?
         .iter()
-        .map(manhattan_distance)
+        .map(Intersection::distance)
         .min()
-        .unwrap();
-    println!("Part 1: {}", closest_intersection_distance);
+        .ok_or("no intersection")?;
+    Ok(closest_intersection_distance.to_string())
 }
 
-pub fn solve_part_two(test: bool) {
-    let wires = load_data(test);
-    let intersections = wires[0].intersections(&wires[1]);
-    let closest_intersection_distance: usize = intersections
+pub fn solve_part_two(wires: &[Wire], params: &Params) -> Result<String, String> {
+    let closest_intersection_steps = intersections(wires, params)?
         .iter()
-        .map(|intersection| -> usize {
-            let dist1 = wires[0]
-                .wire
-                .iter()
-                .position(|position| position == *intersection)
-                .expect("Oops...");
-            let dist2 = wires[1]
-                .wire
-                .iter()
-                .position(|position| position == *intersection)
-                .expect("Oops...");
-            dist1 + dist2
-        })
+        .map(Intersection::total_steps)
         .min()
-        .unwrap();
-    println!("Part 2: {}", closest_intersection_distance);
+        .ok_or("no intersection")?;
+    Ok(closest_intersection_steps.to_string())
 }
 
-struct Wire {
-    wire: Vec<(i32, i32)>,
+/// `render=true` draws the wires as in the puzzle, and `svg=<file>` writes them
+/// as SVG.
+pub fn report(wires: &[Wire], params: &Params) -> Result<String, String> {
+    let mut report = String::new();
+    if params.get_or("render", false)? {
+        _ = writeln!(report, "{}", render(wires));
+    }
+    if let Some(path) = params.get::<String>("svg")? {
+        fs::write(&path, svg(wires, &intersections(wires, params)?))
+            .map_err(|e| format!("cannot write {}: {}", path, e))?;
+    }
+    Ok(report)
 }
 
-impl Wire {
-    pub fn new(directions: Vec<Direction>) -> Self {
-        let mut wire = Vec::new();
-
-        let mut position = (0, 0);
-        wire.push(position);
-
-        for direction in directions {
-            match direction {
-                Direction::Down(distance) => {
-                    for _ in 0..distance {
-                        position = (position.0, position.1 - 1);
-                        wire.push(position);
-                    }
-                }
-                Direction::Left(distance) => {
-                    for _ in 0..distance {
-                        position = (position.0 - 1, position.1);
-                        wire.push(position);
-                    }
-                }
-                Direction::Right(distance) => {
-                    for _ in 0..distance {
-                        position = (position.0 + 1, position.1);
-                        wire.push(position);
-                    }
-                }
-                Direction::Up(distance) => {
-                    for _ in 0..distance {
-                        position = (position.0, position.1 + 1);
-                        wire.push(position);
-                    }
+/// Intersections between the wires: the points every wire goes through with
+/// `intersections=all`, the default, or those any two wires share with
+/// `intersections=pairwise`.
+fn intersections(wires: &[Wire], params: &Params) -> Result<Vec<Intersection>, String> {
+    match params
+        .get_or("intersections", String::from("all"))?
+        .as_str()
+    {
+        "all" => Ok(common_intersections(wires)),
+        "pairwise" => Ok(wires
+            .iter()
+            .tuple_combinations()
+            .flat_map(|(wire, other)| wire.intersections(other))
+            .collect()),
+        mode => Err(format!("unknown intersection mode {}", mode)),
+    }
+}
+
+fn common_intersections(wires: &[Wire]) -> Vec<Intersection> {
+    match wires {
+        [first, second, rest @ ..] => first
+            .intersections(second)
+            .into_iter()
+            .filter_map(|mut intersection| {
+                for wire in rest {
+                    intersection
+                        .steps
+                        .push(wire.steps_to(&intersection.position)?);
                 }
-            }
-        }
+                Some(intersection)
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
 
-        Self { wire }
+/// Point, other than the origin, where wires cross, with the number of steps
+/// each wire takes to first reach it.
+pub struct Intersection {
+    position: Coordinates,
+    steps: Vec<i32>,
+}
+
+impl Intersection {
+    fn distance(&self) -> i32 {
+        self.position.manhattan()
     }
 
-    pub fn intersections<'a>(&'a self, other: &'a Wire) -> HashSet<&'a (i32, i32)> {
-        let mut positions: HashSet<&(i32, i32)> = HashSet::new();
-        self.wire
-            .iter()
-            .filter(|position| position != &&(0, 0))
-            .for_each(|position| _ = positions.insert(position));
-        other
-            .wire
-            .iter()
-            .filter(|position| positions.contains(position))
-            .collect()
+    fn total_steps(&self) -> i32 {
+        self.steps.iter().sum()
     }
 }
 
-#[derive(Debug)]
-enum Direction {
-    Down(i32),
-    Left(i32),
-    Right(i32),
-    Up(i32),
+/// Straight part of a wire, along with the length of the wire before it.
+struct Segment {
+    start: Coordinates,
+    end: Coordinates,
+    steps: i32,
 }
 
-fn down(input: &str) -> IResult<&str, Direction> {
-    map((tag("D"), digit1), |(_tag, distance): (&str, &str)| {
-        let distance = distance.parse::<i32>().expect("Oops...");
-        Direction::Down(distance)
-    })
-    .parse(input)
+impl Segment {
+    fn bounds(&self) -> Bounds {
+        let mut bounds = Bounds::from_point(self.start);
+        bounds.include(&self.end);
+        bounds
+    }
+
+    fn steps_to(&self, position: &Coordinates) -> Option<i32> {
+        self.bounds()
+            .contains(position)
+            .then(|| self.steps + (position - &self.start).manhattan())
+    }
 }
 
-fn left(input: &str) -> IResult<&str, Direction> {
-    map((tag("L"), digit1), |(_tag, distance): (&str, &str)| {
-        let distance = distance.parse::<i32>().expect("Oops...");
-        Direction::Left(distance)
-    })
-    .parse(input)
+pub struct Wire {
+    segments: Vec<Segment>,
 }
 
-fn right(input: &str) -> IResult<&str, Direction> {
-    map((tag("R"), digit1), |(_tag, distance): (&str, &str)| {
-        let distance = distance.parse::<i32>().expect("Oops...");
-        Direction::Right(distance)
-    })
-    .parse(input)
+impl Wire {
+    fn new(steps: Vec<(Direction, i32)>) -> Self {
+        let mut segments = Vec::with_capacity(steps.len());
+
+        let mut start = Coordinates::origin();
+        let mut length = 0;
+        for (direction, distance) in steps {
+            let end = direction.step(&start, distance);
+            segments.push(Segment {
+                start,
+                end,
+                steps: length,
+            });
+            start = end;
+            length += distance;
+        }
+
+        Self { segments }
+    }
+
+    /// Number of steps the wire takes to first reach `position`, if it does.
+    fn steps_to(&self, position: &Coordinates) -> Option<i32> {
+        self.segments
+            .iter()
+            .find_map(|segment| segment.steps_to(position))
+    }
+
+    pub fn intersections(&self, other: &Wire) -> Vec<Intersection> {
+        let mut positions = BTreeSet::new();
+        for segment in self.segments.iter() {
+            for other_segment in other.segments.iter() {
+                if let Some(common) = segment.bounds().intersection(&other_segment.bounds()) {
+                    positions.extend(common.points());
+                }
+            }
+        }
+        positions.remove(&Coordinates::origin());
+
+        positions
+            .into_iter()
+            .map(|position| Intersection {
+                steps: vec![
+                    self.steps_to(&position).expect("Oops..."),
+                    other.steps_to(&position).expect("Oops..."),
+                ],
+                position,
+            })
+            .collect()
+    }
 }
 
-fn up(input: &str) -> IResult<&str, Direction> {
-    map((tag("U"), digit1), |(_tag, distance): (&str, &str)| {
-        let distance = distance.parse::<i32>().expect("Oops...");
-        Direction::Up(distance)
-    })
-    .parse(input)
+/// Colours of the wires in SVG exports, reused when there are more wires.
+const COLOURS: [&str; 6] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#9467bd", "#8c564b", "#e377c2",
+];
+
+/// Draws the wires like the puzzle's examples: `-` and `|` along the wires, `+`
+/// where they turn or cross themselves, `X` where different wires cross and `o`
+/// at the origin.
+fn render(wires: &[Wire]) -> String {
+    let mut diagram: SparseGrid<(usize, char)> = SparseGrid::new();
+    let mut draw = |position: Coordinates, wire: usize, glyph: char| {
+        let glyph = match diagram.get(&position) {
+            Some(&(other, _)) if other != wire => 'X',
+            Some(&(_, 'X')) => 'X',
+            Some(&(_, existing)) if existing != glyph => '+',
+            _ => glyph,
+        };
+        diagram.insert(position, (wire, glyph));
+    };
+
+    for (index, wire) in wires.iter().enumerate() {
+        for (number, segment) in wire.segments.iter().enumerate() {
+            let glyph = if segment.start.get_y() == segment.end.get_y() {
+                '-'
+            } else {
+                '|'
+            };
+            for position in segment.bounds().points() {
+                if position != segment.start {
+                    draw(position, index, glyph);
+                }
+            }
+            if number > 0 {
+                draw(segment.start, index, '+');
+            }
+        }
+    }
+    diagram.insert(Coordinates::origin(), (usize::MAX, 'o'));
+
+    diagram
+        .display(|cell| cell.map_or('.', |&(_, glyph)| glyph))
+        .to_string()
 }
 
-fn wire(input: &str) -> IResult<&str, Wire> {
-    map(
-        separated_list1(tag(","), alt((down, left, right, up))),
-        Wire::new,
+/// Draws the wires as SVG, circling the intersection closest to the origin in
+/// red and boxing the one closest along the wires in blue.
+fn svg(wires: &[Wire], intersections: &[Intersection]) -> String {
+    let corners = wires
+        .iter()
+        .flat_map(|wire| wire.segments.iter().map(|segment| &segment.end));
+    let mut bounds = Bounds::from_point(Coordinates::origin());
+    corners.for_each(|corner| bounds.include(corner));
+
+    // Scale the strokes and markers with the diagram, so that they show at any size.
+    let size = bounds.width().max(bounds.height()) as f64;
+    let margin = size * 0.05;
+    let stroke = size * 0.005;
+    let radius = size * 0.01;
+
+    let mut svg = String::new();
+    _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        bounds.min.get_x() as f64 - margin,
+        bounds.min.get_y() as f64 - margin,
+        (bounds.width() - 1) as f64 + 2.0 * margin,
+        (bounds.height() - 1) as f64 + 2.0 * margin,
+    );
+
+    for (index, wire) in wires.iter().enumerate() {
+        let points = iter::once(Coordinates::origin())
+            .chain(wire.segments.iter().map(|segment| segment.end))
+            .map(|point| format!("{},{}", point.get_x(), point.get_y()))
+            .join(" ");
+        _ = writeln!(
+            svg,
+            r#"  <polyline fill="none" stroke="{}" stroke-width="{}" points="{}"/>"#,
+            COLOURS[index % COLOURS.len()],
+            stroke,
+            points
+        );
+    }
+
+    _ = writeln!(
+        svg,
+        r#"  <circle cx="0" cy="0" r="{}" fill="black"><title>Origin</title></circle>"#,
+        radius
+    );
+    if let Some(closest) = intersections.iter().min_by_key(|i| i.distance()) {
+        _ = writeln!(
+            svg,
+            r#"  <circle cx="{}" cy="{}" r="{}" fill="none" stroke="red" stroke-width="{}"><title>Closest by distance: {}</title></circle>"#,
+            closest.position.get_x(),
+            closest.position.get_y(),
+            2.0 * radius,
+            stroke,
+            closest.distance()
+        );
+    }
+    if let Some(closest) = intersections.iter().min_by_key(|i| i.total_steps()) {
+        _ = writeln!(
+            svg,
+            r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="blue" stroke-width="{}"><title>Closest by steps: {}</title></rect>"#,
+            closest.position.get_x() as f64 - 2.0 * radius,
+            closest.position.get_y() as f64 - 2.0 * radius,
+            4.0 * radius,
+            4.0 * radius,
+            stroke,
+            closest.total_steps()
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn step(input: &str) -> IResult<&str, (Direction, i32)> {
+    map_opt(
+        (one_of("UDLR"), digit1),
+        |(direction, distance): (char, &str)| {
+            let direction = Direction::try_from(direction).ok()?;
+            let distance = distance.parse::<i32>().ok()?;
+            Some((direction, distance))
+        },
     )
     .parse(input)
 }
 
-fn manhattan_distance(position: &&(i32, i32)) -> i32 {
-    position.0.abs() + position.1.abs()
+fn wire(input: &str) -> IResult<&str, Wire> {
+    map(separated_list1(tag(","), step), Wire::new).parse(input)
 }
 
-fn load_data(test: bool) -> Vec<Wire> {
-    aoc2019::load_data(3, test)
+pub fn parse(input: &Input) -> Result<Vec<Wire>, String> {
+    input
+        .data()
         .lines()
-        .map(|l| wire(l).expect("Oops...").1)
+        .map(|l| {
+            all_consuming(wire)
+                .parse(l)
+                .map(|(_, wire)| wire)
+                .map_err(|_| format!("invalid wire {}", l))
+        })
         .collect()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn example() -> Vec<Wire> {
+        let input = Input::new(
+            String::from("R8,U5,L5,D3\nU7,R6,D4,L4\n"),
+            Params::default(),
+        );
+        parse(&input).unwrap()
+    }
+
+    #[test]
+    fn test_render() {
+        let diagram = [
+            "+-----+..",
+            "|.....|..",
+            "|..+--X-+",
+            "|..|..|.|",
+            "|.-X--+.|",
+            "|..|....|",
+            "|.......|",
+            "o-------+",
+        ];
+        assert_eq!(diagram.join("\n"), render(&example()));
+    }
+
+    #[test]
+    fn test_svg() {
+        let wires = example();
+        let svg = svg(&wires, &common_intersections(&wires));
+        assert!(svg.starts_with("<svg ") && svg.ends_with("</svg>\n"));
+        assert_eq!(2, svg.matches("<polyline").count());
+        assert!(svg.contains(r#"<circle cx="3" cy="-3" r="0.18" fill="none" stroke="red""#));
+        assert!(svg.contains(r#"<rect x="5.82" y="-5.18" width="0.36" height="0.36""#));
+    }
+
+    #[test]
+    fn test_no_intersection() {
+        let params = Params::default();
+        let single = parse(&Input::new(String::from("R8,U5,L5,D3\n"), params.clone())).unwrap();
+        assert_eq!(
+            Err(String::from("no intersection")),
+            solve_part_one(&single, &params)
+        );
+
+        let parallel = parse(&Input::new(String::from("R8\nL8\n"), params.clone())).unwrap();
+        assert_eq!(
+            Err(String::from("no intersection")),
+            solve_part_two(&parallel, &params)
+        );
+    }
+}
@@ -1,52 +1,526 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
 use regex::Regex;
 
-pub fn solve_part_one(test: bool) {
-    let (start, end) = load_data(test);
-    let count = (start..=end).filter(|i| meets_criteria(*i)).count();
-    println!("Part 1: {}", count);
+use aoc2019::{Input, Params};
+
+/// Number of digits of the largest `u64`.
+const MAX_DIGITS: usize = 20;
+
+/// Rules of part one, unless overridden with the `rules` parameter.
+const PART_ONE_RULES: &str = "non-decreasing,run>=2";
+/// Rules of part two, unless overridden with the `rules` parameter.
+const PART_TWO_RULES: &str = "non-decreasing,run=2";
+
+pub fn solve_part_one(range: &(u64, u64), params: &Params) -> Result<String, String> {
+    solve(range, params, PART_ONE_RULES)
+}
+
+pub fn solve_part_two(range: &(u64, u64), params: &Params) -> Result<String, String> {
+    solve(range, params, PART_TWO_RULES)
 }
-pub fn solve_part_two(test: bool) {
-    let (start, end) = load_data(test);
-    let count = (start..=end).filter(|i| meets_criteria_part_2(*i)).count();
-    println!("Part 2: {}", count);
+
+/// Counts the passwords in the range or, with the `nth` parameter, finds the
+/// nth one, counting from the end when negative.
+fn solve(
+    &(start, end): &(u64, u64),
+    params: &Params,
+    default_rules: &str,
+) -> Result<String, String> {
+    let rules = params
+        .get("rules")?
+        .unwrap_or_else(|| default_rules.parse::<Rules>().expect("Oops..."));
+    let mut passwords = rules.passwords(start, end)?;
+
+    let password = match params.get::<i64>("nth")? {
+        None => return Ok(passwords.len().to_string()),
+        Some(0) => return Err(String::from("passwords are numbered from 1")),
+        Some(n) if n > 0 => passwords.nth(n as usize - 1),
+        Some(n) => passwords.nth_back(n.unsigned_abs() as usize - 1),
+    };
+    password
+        .map(|password| password.to_string())
+        .ok_or_else(|| String::from("not that many passwords"))
 }
 
-fn meets_criteria(value: u32) -> bool {
-    let digits: Vec<u32> = value
-        .to_string()
-        .chars()
-        .map(|d| d.to_digit(10).unwrap())
-        .collect();
-    (1..digits.len()).all(|i| digits[i] >= digits[i - 1]) // only increasing digits
-        && (1..digits.len()).any(|i| digits[i] == digits[i - 1]) // two consecutive identical digits
+/// Constraint on the digits of a password, written as in the `rules` parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// `non-decreasing`: digits never decrease from left to right.
+    NonDecreasing,
+    /// `non-increasing`: digits never increase from left to right.
+    NonIncreasing,
+    /// `run>=N`: some digit is repeated at least N times in a row.
+    RunAtLeast(u32),
+    /// `run=N`: some digit is repeated exactly N times in a row.
+    RunExactly(u32),
+    /// `sum>=N`: the digits add up to at least N.
+    SumAtLeast(u32),
+    /// `sum<=N`: the digits add up to at most N.
+    SumAtMost(u32),
+    /// `length=N`: the password has exactly N digits.
+    Length(u32),
 }
 
-fn meets_criteria_part_2(value: u32) -> bool {
-    let digits: Vec<u32> = value
-        .to_string()
-        .chars()
-        .map(|d| d.to_digit(10).unwrap())
-        .collect();
-    (1..digits.len()).all(|i| digits[i] >= digits[i - 1]) // only increasing digits
-        && (1..digits.len()).any(|i| {
-            let mut result = digits[i] == digits[i - 1];
-            if i > 1 {
-                result = result && digits[i] != digits[i - 2];
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |value: &str| {
+            value
+                .parse()
+                .map_err(|_| format!("Invalid number in rule {}", s))
+        };
+
+        match s.trim() {
+            "non-decreasing" => Ok(Self::NonDecreasing),
+            "non-increasing" => Ok(Self::NonIncreasing),
+            rule => {
+                if let Some(n) = rule.strip_prefix("run>=") {
+                    Ok(Self::RunAtLeast(number(n)?))
+                } else if let Some(n) = rule.strip_prefix("run=") {
+                    Ok(Self::RunExactly(number(n)?))
+                } else if let Some(n) = rule.strip_prefix("sum>=") {
+                    Ok(Self::SumAtLeast(number(n)?))
+                } else if let Some(n) = rule.strip_prefix("sum<=") {
+                    Ok(Self::SumAtMost(number(n)?))
+                } else if let Some(n) = rule.strip_prefix("length=") {
+                    Ok(Self::Length(number(n)?))
+                } else {
+                    Err(format!("Unknown rule {}", rule))
+                }
+            }
+        }
+    }
+}
+
+/// What the rules need to know about the digits read so far. Counters are
+/// capped just above the largest value the rules look at, so that passwords
+/// the rules cannot tell apart share the same state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+struct State {
+    /// Last digit, `None` until a non-zero digit has been read.
+    last: Option<u8>,
+    run: u32,
+    length: u32,
+    sum: u32,
+    /// Rules already satisfied, one bit per rule.
+    satisfied: u32,
+}
+
+/// Passwords matching all of a set of rules, counted with a digit DP.
+pub struct Rules {
+    rules: Vec<Rule>,
+    run_cap: u32,
+    length_cap: u32,
+    sum_cap: u32,
+}
+
+impl Rules {
+    pub fn new(rules: Vec<Rule>) -> Self {
+        assert!(rules.len() <= 32, "Too many rules");
+        let cap =
+            |f: fn(&Rule) -> Option<u32>| rules.iter().filter_map(f).max().map_or(0, |n| n + 1);
+
+        Self {
+            run_cap: cap(|rule| match rule {
+                Rule::RunAtLeast(n) | Rule::RunExactly(n) => Some(*n),
+                _ => None,
+            }),
+            length_cap: cap(|rule| match rule {
+                Rule::Length(n) => Some(*n),
+                _ => None,
+            }),
+            sum_cap: cap(|rule| match rule {
+                Rule::SumAtLeast(n) | Rule::SumAtMost(n) => Some(*n),
+                _ => None,
+            }),
+            rules,
+        }
+    }
+
+    /// Passwords between `start` and `end`, both included, in increasing order.
+    /// Fails if there are more of them than a `u64` can count.
+    pub fn passwords(&self, start: u64, end: u64) -> Result<Passwords<'_>, String> {
+        let mut memo = HashMap::new();
+        let mut count_up_to = |max| {
+            self.count_up_to(max, &mut memo)
+                .ok_or_else(|| format!("too many passwords up to {}", max))
+        };
+        let front = match start {
+            0 => 0,
+            start => count_up_to(start - 1)?,
+        };
+        let back = count_up_to(end)?.max(front);
+
+        Ok(Passwords {
+            rules: self,
+            memo,
+            front,
+            back,
+        })
+    }
+
+    /// Number of passwords between 0 and `max`, walking down the digits of
+    /// `max` and counting the completions of every smaller prefix. `None` if
+    /// the count overflows.
+    fn count_up_to(&self, max: u64, memo: &mut HashMap<(State, usize), u64>) -> Option<u64> {
+        let digits = digits(max);
+        let mut count: u64 = 0;
+        let mut state = State::default();
+
+        for (index, &digit) in digits.iter().enumerate() {
+            let remaining = digits.len() - index - 1;
+            for smaller in 0..digit {
+                if let Some(next) = self.push(&state, smaller) {
+                    count = count.checked_add(self.completions(&next, remaining, memo)?)?;
+                }
             }
-            if i < digits.len() - 1 {
-                result = result && digits[i] != digits[i + 1];
+            match self.push(&state, digit) {
+                Some(next) => state = next,
+                None => return Some(count),
+            }
+        }
+
+        count.checked_add(u64::from(self.accepts(&state)))
+    }
+
+    /// Number of ways to append `remaining` digits to `state` and get a
+    /// password, or `None` if the count overflows.
+    fn completions(
+        &self,
+        state: &State,
+        remaining: usize,
+        memo: &mut HashMap<(State, usize), u64>,
+    ) -> Option<u64> {
+        if remaining == 0 {
+            return Some(u64::from(self.accepts(state)));
+        }
+        if let Some(&count) = memo.get(&(*state, remaining)) {
+            return Some(count);
+        }
+
+        let count = (0..10)
+            .filter_map(|digit| self.push(state, digit))
+            .try_fold(0u64, |count, next| {
+                count.checked_add(self.completions(&next, remaining - 1, memo)?)
+            })?;
+        memo.insert((*state, remaining), count);
+        Some(count)
+    }
+
+    /// State after reading `digit`, or `None` if no password can start this way.
+    fn push(&self, state: &State, digit: u8) -> Option<State> {
+        match state.last {
+            // Leading zeros are not part of the password.
+            None if digit == 0 => Some(*state),
+            _ => self.append(state, digit),
+        }
+    }
+
+    fn append(&self, state: &State, digit: u8) -> Option<State> {
+        let mut next = *state;
+        if state.last == Some(digit) {
+            next.run = (state.run + 1).min(self.run_cap);
+        } else {
+            next.satisfied |= self.closed_runs(state);
+            next.run = 1.min(self.run_cap);
+        }
+        next.last = Some(digit);
+        next.length = (state.length + 1).min(self.length_cap);
+        next.sum = (state.sum + u32::from(digit)).min(self.sum_cap);
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            match *rule {
+                Rule::NonDecreasing if state.last.is_some_and(|last| digit < last) => return None,
+                Rule::NonIncreasing if state.last.is_some_and(|last| digit > last) => return None,
+                Rule::RunAtLeast(n) if next.run >= n => next.satisfied |= 1 << index,
+                Rule::SumAtMost(n) if next.sum > n => return None,
+                Rule::Length(n) if next.length > n => return None,
+                _ => (),
+            }
+        }
+
+        Some(next)
+    }
+
+    /// Rules satisfied by the run of digits `state` ends with, once it is over.
+    fn closed_runs(&self, state: &State) -> u32 {
+        self.rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| state.last.is_some() && **rule == Rule::RunExactly(state.run))
+            .fold(0, |satisfied, (index, _)| satisfied | 1 << index)
+    }
+
+    fn accepts(&self, state: &State) -> bool {
+        // Only zeros were read: the password is 0.
+        let state = match state.last {
+            None => match self.append(state, 0) {
+                Some(state) => state,
+                None => return false,
+            },
+            Some(_) => *state,
+        };
+        let satisfied = state.satisfied | self.closed_runs(&state);
+
+        self.rules
+            .iter()
+            .enumerate()
+            .all(|(index, rule)| match *rule {
+                Rule::RunAtLeast(_) | Rule::RunExactly(_) => satisfied & 1 << index != 0,
+                Rule::SumAtLeast(n) => state.sum >= n,
+                Rule::Length(n) => state.length == n,
+                _ => true,
+            })
+    }
+}
+
+/// Lazy sequence of the passwords in a range. Passwords are found from their rank
+/// among all passwords, digit by digit, so skipping ahead costs nothing.
+pub struct Passwords<'a> {
+    rules: &'a Rules,
+    memo: HashMap<(State, usize), u64>,
+    /// Rank of the next password from the front, included.
+    front: u64,
+    /// Rank of the next password from the back, excluded.
+    back: u64,
+}
+
+impl Passwords<'_> {
+    /// The password with `rank` passwords below it.
+    fn select(&mut self, mut rank: u64) -> u64 {
+        let mut state = State::default();
+        let mut password = 0;
+
+        for remaining in (0..MAX_DIGITS).rev() {
+            for digit in 0..10 {
+                let Some(next) = self.rules.push(&state, digit) else {
+                    continue;
+                };
+                // Too many completions to count are still more than `rank`.
+                let count = self
+                    .rules
+                    .completions(&next, remaining, &mut self.memo)
+                    .unwrap_or(u64::MAX);
+                if rank < count {
+                    state = next;
+                    password = password * 10 + u64::from(digit);
+                    break;
+                }
+                rank -= count;
             }
-            result
-        }) // at least one group of two consecutive identical digits, but not more
+        }
+
+        password
+    }
+}
+
+impl Iterator for Passwords<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.nth(0)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = self.front.saturating_add(n as u64).min(self.back);
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.select(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.back - self.front) as usize;
+        (len, Some(len))
+    }
+
+    fn count(self) -> usize {
+        self.len()
+    }
+}
+
+impl DoubleEndedIterator for Passwords<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.nth_back(0)
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.back = self.back.saturating_sub(n as u64).max(self.front);
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.select(self.back))
+    }
+}
+
+impl ExactSizeIterator for Passwords<'_> {}
+
+impl FromStr for Rules {
+    type Err = String;
+
+    /// Parses comma-separated rules, e.g. `non-decreasing,run=2`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rules = s.split(',').map(str::parse).collect::<Result<_, _>>()?;
+        Ok(Self::new(rules))
+    }
+}
+
+fn digits(value: u64) -> Vec<u8> {
+    value
+        .to_string()
+        .bytes()
+        .map(|digit| digit - b'0')
+        .collect()
 }
 
-fn load_data(test: bool) -> (u32, u32) {
-    let input = aoc2019::load_data(4, test);
+pub fn parse(input: &Input) -> Result<(u64, u64), String> {
+    let input = input.data();
     let range = input.trim_end();
     let regex = Regex::new("^(\\d+)-(\\d+)$").unwrap();
-    let captures = regex.captures(range).unwrap();
-    (
-        captures[1].parse::<u32>().unwrap(),
-        captures[2].parse::<u32>().unwrap(),
-    )
+    let error = |e: &dyn fmt::Display| format!("invalid range {}: {}", range, e);
+    let captures = regex
+        .captures(range)
+        .ok_or_else(|| error(&"expected start-end"))?;
+    Ok((
+        captures[1].parse::<u64>().map_err(|e| error(&e))?,
+        captures[2].parse::<u64>().map_err(|e| error(&e))?,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn count(rules: &str, start: u64, end: u64) -> usize {
+        let rules = rules.parse::<Rules>().unwrap();
+        rules.passwords(start, end).unwrap().len()
+    }
+
+    fn accepts(rules: &str, password: u64) -> bool {
+        count(rules, password, password) == 1
+    }
+
+    #[test]
+    fn test_rule_from_str() {
+        assert_eq!(Ok(Rule::RunAtLeast(2)), " run>=2 ".parse());
+        assert_eq!(Ok(Rule::RunExactly(3)), "run=3".parse());
+        assert_eq!(
+            Err(String::from("Invalid number in rule run>=two")),
+            "run>=two".parse::<Rule>()
+        );
+        assert_eq!(
+            Err(String::from("Invalid number in rule sum<=-1")),
+            "sum<=-1".parse::<Rule>()
+        );
+        assert_eq!(
+            Err(String::from("Unknown rule even")),
+            "even".parse::<Rule>()
+        );
+        assert!("non-decreasing,".parse::<Rules>().is_err());
+    }
+
+    #[test]
+    fn test_runs() {
+        assert!(accepts("run=2", 111122));
+        assert!(!accepts("run=2", 111123));
+        assert!(accepts("run=4", 111122));
+        assert!(accepts("run>=2", 111123));
+        assert!(accepts("run>=4", 111122));
+        assert!(!accepts("run>=5", 111122));
+
+        assert!(accepts("run=2", 112233));
+        assert!(!accepts("run=3", 112233));
+        assert!(accepts("run>=2", 112233));
+        assert!(!accepts("run>=3", 112233));
+
+        assert!(accepts("non-decreasing,run>=2", 123444));
+        assert!(!accepts("non-decreasing,run=2", 123444));
+    }
+
+    #[test]
+    fn test_sum_and_length() {
+        let rules = "sum>=10,sum<=12,length=2";
+        let expected = (0..=999)
+            .filter(|n: &u64| {
+                let sum: u8 = digits(*n).iter().sum();
+                (10..=12).contains(&sum) && digits(*n).len() == 2
+            })
+            .count();
+        assert_eq!(expected, count(rules, 0, 999));
+        assert!(accepts(rules, 19));
+        assert!(!accepts(rules, 109));
+        assert!(accepts("length=1,sum<=0", 0));
+    }
+
+    #[test]
+    fn test_count() {
+        let rules = "non-decreasing,run>=2";
+        let expected = (111..=123)
+            .filter(|n| {
+                let digits = digits(*n);
+                digits.is_sorted() && digits.windows(2).any(|pair| pair[0] == pair[1])
+            })
+            .count();
+        assert_eq!(10, expected);
+        assert_eq!(expected, count(rules, 111, 123));
+        assert_eq!(0, count(rules, 123, 111));
+
+        // Non-decreasing numbers of n digits are the C(n + 8, 8) multisets of
+        // digits 1 to 9 and, without a run, the C(9, n) sets of them. Summed
+        // over 1 to 15 digits, that is C(24, 9) - 1 - (2^9 - 1).
+        assert_eq!(1_306_992, count(rules, 0, 10u64.pow(15)));
+    }
+
+    #[test]
+    fn test_passwords() {
+        let rules = "non-decreasing,run=2".parse::<Rules>().unwrap();
+        let all = rules.passwords(111111, 112233).unwrap().collect::<Vec<_>>();
+        assert_eq!(Some(&111122), all.first());
+        assert_eq!(Some(&112233), all.last());
+
+        let mut passwords = rules.passwords(111111, 112233).unwrap();
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        while let Some(password) = passwords.next() {
+            front.push(password);
+            assert_eq!(all.len() - front.len() - back.len(), passwords.len());
+            match passwords.next_back() {
+                Some(password) => back.push(password),
+                None => break,
+            }
+        }
+        assert_eq!(None, passwords.next_back());
+        front.extend(back.iter().rev());
+        assert_eq!(all, front);
+
+        let mut passwords = rules.passwords(111111, 112233).unwrap();
+        assert_eq!(Some(all[2]), passwords.nth(2));
+        assert_eq!(all.len() - 3, passwords.len());
+        assert_eq!(None, passwords.nth(all.len()));
+        assert_eq!(0, passwords.len());
+        assert_eq!(None, passwords.next());
+        assert_eq!(None, passwords.next_back());
+    }
+
+    #[test]
+    fn test_next_candidate() {
+        let rules = "run=2".parse::<Rules>().unwrap();
+        let mut passwords = rules.passwords(123444, 999999).unwrap();
+        assert_eq!(Some(123445), passwords.next());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let rules = "run>=1".parse::<Rules>().unwrap();
+        assert!(rules.passwords(0, u64::MAX).is_err());
+
+        let mut passwords = rules.passwords(0, u64::MAX - 1).unwrap();
+        assert_eq!(usize::MAX, passwords.len());
+        assert_eq!(Some(u64::MAX - 1), passwords.next_back());
+        assert_eq!(Some(u64::MAX / 2), passwords.nth(usize::MAX / 2));
+    }
 }
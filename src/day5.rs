@@ -1,7 +1,7 @@
-use crate::intcode::Intcode;
+use aoc2019::{Input, Params, intcode::Intcode};
 
-pub fn solve_part_one(test: bool) {
-    let mut program = load_data(test);
+pub fn solve_part_one(program: &Intcode, _params: &Params) -> Result<String, String> {
+    let mut program = program.clone();
     program.add_input(1);
     let mut output = 0;
     loop {
@@ -12,16 +12,16 @@ pub fn solve_part_one(test: bool) {
             break;
         }
     }
-    println!("Part 1: {}", output);
+    Ok(output.to_string())
 }
 
-pub fn solve_part_two(test: bool) {
-    let mut program = load_data(test);
+pub fn solve_part_two(program: &Intcode, _params: &Params) -> Result<String, String> {
+    let mut program = program.clone();
     program.add_input(5);
     program.execute();
-    println!("Part 2: {}", program.next_output().unwrap());
+    Ok(program.next_output().unwrap().to_string())
 }
 
-fn load_data(test: bool) -> Intcode {
-    Intcode::from(aoc2019::load_data(5, test).trim())
+pub fn parse(input: &Input) -> Result<Intcode, String> {
+    Ok(Intcode::from(input.data().trim()))
 }
@@ -1,60 +1,114 @@
-use std::collections::HashMap;
+use std::{fmt::Write, fs};
 
-use array_tool::vec::Intersect;
-use itertools::Itertools;
+use aoc2019::{
+    Input, Params,
+    orbits::{ExportOptions, OrbitMap},
+};
 
-pub fn solve_part_one(test: bool) {
-    let orbits = load_data(test);
+pub fn solve_part_one(orbits: &OrbitMap, _params: &Params) -> Result<String, String> {
+    Ok(orbits.total_orbits().to_string())
+}
 
-    let mut orbit_count = 0;
-    for orbit in &orbits {
-        orbit_count += 1;
-        let mut parent = orbit.1;
-        while orbits.contains_key(parent) {
-            orbit_count += 1;
-            parent = orbits.get(parent).unwrap();
-        }
+/// Transfers between the objects the `from` and `to` parameters orbit, `YOU`
+/// and `SAN` by default.
+pub fn solve_part_two(orbits: &OrbitMap, params: &Params) -> Result<String, String> {
+    let (from, to) = endpoints(orbits, params)?;
+    let transfers = orbits.transfers(from, to).ok_or_else(|| {
+        format!(
+            "no transfers from {} to {}",
+            orbits.name(from),
+            orbits.name(to)
+        )
+    })?;
+    Ok(transfers.to_string())
+}
+
+/// `tree=true` shows the map as an indented tree and `dot=<file>` writes it
+/// in the DOT format, both following the `highlight=A,B` and `collapse=N`
+/// parameters. `route=true` shows the objects visited between `from` and `to`,
+/// `within=K` the objects `from` can orbit after at most K transfers and
+/// `diameter=true` the longest path in the map.
+pub fn report(orbits: &OrbitMap, params: &Params) -> Result<String, String> {
+    let names = |objects: Vec<usize>| {
+        objects
+            .into_iter()
+            .map(|object| orbits.name(object))
+            .collect::<Vec<_>>()
+    };
+    let mut report = String::new();
+
+    let options = export_options(orbits, params)?;
+    if params.get_or("tree", false)? {
+        report.push_str(&orbits.to_tree(&options));
+    }
+    if let Some(path) = params.get::<String>("dot")? {
+        fs::write(&path, orbits.to_dot(&options))
+            .map_err(|e| format!("cannot write {}: {}", path, e))?;
+    }
+
+    if params.get_or("route", false)? {
+        let (from, to) = endpoints(orbits, params)?;
+        let route = orbits.transfer_path(from, to).unwrap_or_default();
+        _ = writeln!(report, "Route: {}", names(route).join(" -> "));
+    }
+    if let Some(hops) = params.get("within")? {
+        let (from, _) = endpoints(orbits, params)?;
+        _ = writeln!(
+            report,
+            "Within {} transfers of {}: {}",
+            hops,
+            orbits.name(from),
+            names(orbits.within(from, hops)).join(", ")
+        );
     }
-    println!("Part 1: {}", orbit_count);
+    if params.get_or("diameter", false)? {
+        let diameter = orbits.diameter().unwrap_or_default();
+        _ = writeln!(
+            report,
+            "Diameter: {} hops, {}",
+            diameter.len().saturating_sub(1),
+            names(diameter).join(" -> ")
+        );
+    }
+
+    Ok(report)
 }
 
-pub fn solve_part_two(test: bool) {
-    let orbits = load_data(test);
-
-    let you_path = get_path_to_com(&"YOU".to_string(), &orbits);
-    let santa_path = get_path_to_com(&"SAN".to_string(), &orbits);
-    let intersection = you_path.intersect(santa_path.clone());
-
-    let distance_from_you_to_intersection =
-        you_path.iter().position(|x| *x == intersection[0]).unwrap();
-    let distance_from_intersection_to_santa = santa_path
-        .iter()
-        .position(|x| *x == intersection[0])
-        .unwrap();
-    println!(
-        "Part 2: {}",
-        distance_from_you_to_intersection + distance_from_intersection_to_santa
-    );
+/// Objects set by the `from` and `to` parameters.
+fn endpoints(orbits: &OrbitMap, params: &Params) -> Result<(usize, usize), String> {
+    Ok((
+        object(orbits, &params.get_or("from", String::from("YOU"))?)?,
+        object(orbits, &params.get_or("to", String::from("SAN"))?)?,
+    ))
 }
 
-fn get_path_to_com<'a>(object: &String, orbits: &'a HashMap<String, String>) -> Vec<&'a String> {
-    let mut path = Vec::new();
-    let mut orbiter = object;
+/// Export options from the `highlight=A,B` and `collapse=N` parameters.
+fn export_options(orbits: &OrbitMap, params: &Params) -> Result<ExportOptions, String> {
+    let highlight = match params.get::<String>("highlight")? {
+        Some(objects) => {
+            let (a, b) = objects
+                .split_once(',')
+                .ok_or_else(|| format!("expected highlight=A,B, got {}", objects))?;
+            Some((object(orbits, a)?, object(orbits, b)?))
+        }
+        None => None,
+    };
 
-    while let Some(parent) = orbits.get(orbiter) {
-        path.push(parent);
-        orbiter = parent;
-    }
+    Ok(ExportOptions {
+        highlight,
+        collapse: params.get("collapse")?,
+    })
+}
 
-    path
+fn object(orbits: &OrbitMap, name: &str) -> Result<usize, String> {
+    orbits
+        .id(name)
+        .ok_or_else(|| format!("no {} in the map", name))
 }
 
-fn load_data(test: bool) -> HashMap<String, String> {
-    aoc2019::load_data(6, test)
-        .trim()
-        .lines()
-        .flat_map(|s| s.rsplit(")"))
-        .map(String::from)
-        .tuples()
-        .collect()
+pub fn parse(input: &Input) -> Result<OrbitMap, String> {
+    input
+        .data()
+        .parse()
+        .map_err(|e| format!("invalid orbit map: {}", e))
 }
@@ -1,67 +1,223 @@
+use std::{fmt::Write, str::FromStr};
+
 use itertools::Itertools;
 
-use crate::intcode::Intcode;
-
-pub fn solve_part_one(test: bool) {
-    let master_program = load_data(test);
-    let result = (0..=4)
-        .permutations(5)
-        .map(|settings| {
-            let mut output = 0;
-
-            for phase in settings {
-                let mut program = master_program.clone();
-                program.add_input(phase);
-                program.add_input(output);
-                program.execute();
-                output = program.next_output().unwrap();
+use aoc2019::{Input, Params, intcode::Intcode};
+
+pub fn solve_part_one(program: &Intcode, params: &Params) -> Result<String, String> {
+    Ok(best(program, params, part_one_circuit())?
+        .signal
+        .to_string())
+}
+
+pub fn solve_part_two(program: &Intcode, params: &Params) -> Result<String, String> {
+    Ok(best(program, params, part_two_circuit())?
+        .signal
+        .to_string())
+}
+
+/// `trace=true` shows the best setting of each part along with the signals
+/// each stage output.
+pub fn report(program: &Intcode, params: &Params) -> Result<String, String> {
+    let mut report = String::new();
+    if params.get_or("trace", false)? {
+        for (part, default) in [(1, part_one_circuit()), (2, part_two_circuit())] {
+            let best = best(program, params, default)?;
+            _ = writeln!(report, "Part {} phases: {:?}", part, best.phases);
+            for (stage, signals) in best.trace.iter().enumerate() {
+                _ = writeln!(report, "Stage {}: {:?}", stage + 1, signals);
             }
+        }
+    }
+    Ok(report)
+}
+
+fn part_one_circuit() -> Circuit {
+    Circuit::new(5, (0..=4).collect(), Topology::Series)
+}
+
+fn part_two_circuit() -> Circuit {
+    Circuit::new(5, (5..=9).collect(), Topology::Feedback)
+}
+
+/// Run giving the highest signal with the puzzle's circuit, or with the one set
+/// by the `stages`, `phases`, `topology` and `signal` parameters.
+fn best(program: &Intcode, params: &Params, default: Circuit) -> Result<Run, String> {
+    let circuit = Circuit {
+        stages: params.get_or("stages", default.stages)?,
+        phases: params
+            .get::<Phases>("phases")?
+            .map_or(default.phases, |phases| phases.0),
+        topology: params.get_or("topology", default.topology)?,
+        signal: params.get_or("signal", default.signal)?,
+    };
+
+    if circuit.stages == 0 {
+        return Err(String::from("the circuit needs at least one stage"));
+    }
+    circuit
+        .best(program)
+        .ok_or_else(|| String::from("not enough phase settings for every stage"))
+}
+
+/// How the amplifiers are wired.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    /// The signal goes through every amplifier once.
+    Series,
+    /// The output of the last amplifier goes back to the first one, until an
+    /// amplifier halts.
+    Feedback,
+}
+
+impl FromStr for Topology {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "series" => Ok(Self::Series),
+            "feedback" => Ok(Self::Feedback),
+            _ => Err(format!("Unknown topology {}", s)),
+        }
+    }
+}
+
+/// Phase settings, written as a range like `5..=9` or a list like `1,3,5`.
+struct Phases(Vec<i64>);
+
+impl FromStr for Phases {
+    type Err = String;
 
-            output
-        })
-        .max()
-        .unwrap();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |value: &str| {
+            value
+                .trim()
+                .parse::<i64>()
+                .map_err(|_| format!("Invalid phase setting {}", value))
+        };
+
+        match s.split_once("..=") {
+            Some((start, end)) => Ok(Self((number(start)?..=number(end)?).collect())),
+            None => Ok(Self(s.split(',').map(number).collect::<Result<_, _>>()?)),
+        }
+    }
+}
 
-    println!("Part 1: {}", result);
+/// Chain of amplifiers running the same program, each given a distinct phase
+/// setting.
+pub struct Circuit {
+    stages: usize,
+    phases: Vec<i64>,
+    topology: Topology,
+    signal: i64,
 }
 
-pub fn solve_part_two(test: bool) {
-    let master_program = load_data(test);
-    let result = (5..10)
-        .permutations(5)
-        .map(|settings| {
-            // Initialize amplifiers
-            let mut amplifiers: Vec<Intcode> = (0..5)
-                .map(|i| {
-                    let mut amplifier = master_program.clone();
-                    amplifier.add_input(settings[i]);
-                    amplifier
-                })
-                .collect();
-
-            let mut output = 0;
-            let mut next = 0;
-            let mut amplifier = &mut amplifiers[next % 5];
-            loop {
-                amplifier.add_input(output);
+/// Outcome of a circuit for one choice of phase settings.
+pub struct Run {
+    pub phases: Vec<i64>,
+    /// Signals output by each stage, in order.
+    pub trace: Vec<Vec<i64>>,
+    /// Last signal output by the circuit.
+    pub signal: i64,
+}
+
+impl Circuit {
+    /// Circuit of `stages` amplifiers, with phase settings taken from `phases`
+    /// and an initial signal of 0.
+    pub fn new(stages: usize, phases: Vec<i64>, topology: Topology) -> Self {
+        Self {
+            stages,
+            phases,
+            topology,
+            signal: 0,
+        }
+    }
+
+    /// Setting giving the highest signal, if there is at least one stage and
+    /// enough phase settings.
+    pub fn best(&self, program: &Intcode) -> Option<Run> {
+        if self.stages == 0 {
+            return None;
+        }
+        self.phases
+            .iter()
+            .copied()
+            .permutations(self.stages)
+            .map(|phases| self.run(program, &phases))
+            .max_by_key(|run| run.signal)
+    }
+
+    /// Runs the circuit with a stage per phase setting. Without stages, the
+    /// signal goes through untouched.
+    pub fn run(&self, program: &Intcode, phases: &[i64]) -> Run {
+        if phases.is_empty() {
+            return Run {
+                phases: Vec::new(),
+                trace: Vec::new(),
+                signal: self.signal,
+            };
+        }
+
+        let mut amplifiers: Vec<Intcode> = phases
+            .iter()
+            .map(|&phase| {
+                let mut amplifier = program.clone();
+                amplifier.add_input(phase);
+                amplifier
+            })
+            .collect();
+
+        let mut trace = vec![Vec::new(); phases.len()];
+        let mut signal = self.signal;
+        'circuit: loop {
+            for (stage, amplifier) in amplifiers.iter_mut().enumerate() {
+                amplifier.add_input(signal);
                 amplifier.execute();
-                if let Some(o) = amplifier.next_output() {
-                    output = o;
-                    next += 1;
-                    amplifier = &mut amplifiers[next % 5];
-                } else {
-                    break;
+                match amplifier.next_output() {
+                    Some(output) => {
+                        signal = output;
+                        trace[stage].push(output);
+                    }
+                    None => break 'circuit,
                 }
             }
 
-            output
-        })
-        .max()
-        .unwrap();
+            if self.topology == Topology::Series {
+                break;
+            }
+        }
+
+        Run {
+            phases: phases.to_vec(),
+            trace,
+            signal,
+        }
+    }
+}
 
-    println!("Part 2: {}", result);
+pub fn parse(input: &Input) -> Result<Intcode, String> {
+    Ok(Intcode::from(input.data().trim()))
 }
 
-fn load_data(test: bool) -> Intcode {
-    Intcode::from(aoc2019::load_data(7, test).trim())
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PROGRAM: &str = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0";
+
+    #[test]
+    fn test_no_stages() {
+        let program = Intcode::from(PROGRAM);
+        for topology in [Topology::Series, Topology::Feedback] {
+            let circuit = Circuit::new(0, (0..=4).collect(), topology);
+            assert!(circuit.best(&program).is_none());
+
+            let run = circuit.run(&program, &[]);
+            assert_eq!(0, run.signal);
+            assert!(run.trace.is_empty());
+        }
+
+        let circuit = Circuit::new(5, (0..=4).collect(), Topology::Series);
+        assert_eq!(Some(43210), circuit.best(&program).map(|run| run.signal));
+    }
 }
@@ -1,86 +1,53 @@
-use core::str;
+use std::{fs, path::Path};
 
-use itertools::Itertools;
+use aoc2019::{
+    Input, Params, ocr,
+    sif::{self, Image, Netpbm},
+};
 
-pub fn solve_part_one(test: bool) {
-    let image = load_data(test);
-
-    let mut layer_number = 0;
-    let mut minimum_zero_count = usize::MAX;
-    for (number, layer) in image.layers.iter().enumerate() {
-        let zero_count = count(0, layer);
-        if zero_count < minimum_zero_count {
-            minimum_zero_count = zero_count;
-            layer_number = number;
-        }
-    }
-
-    let one_count = count(1, image.layer(layer_number));
-    let two_count = count(2, image.layer(layer_number));
-    println!("Part 1: {}", one_count * two_count);
+pub fn solve_part_one(image: &Image, _params: &Params) -> Result<String, String> {
+    let layer = image.layer_with_fewest(0).expect("Oops...");
+    let histogram = image.histogram(layer);
+    Ok((histogram[1] * histogram[2]).to_string())
 }
 
-pub fn solve_part_two(test: bool) {
-    let image = load_data(test);
-    let decoded_image = image.decode();
-
-    println!("Part 2:");
-    decoded_image[..].chunks(image.width).for_each(|line| {
-        println!(
-            "{}",
-            line.iter()
-                .map(|d| match d {
-                    1 => "*",
-                    _ => " ",
+/// Reads the letters of the decoded image, pixels transparent in every layer
+/// taking the colour set by the `fallback` parameter, black by default.
+pub fn solve_part_two(image: &Image, params: &Params) -> Result<String, String> {
+    let decoded_image = image.decode(params.get_or("fallback", sif::BLACK)?);
+
+    // Fall back to the picture when it does not spell letters.
+    Ok(
+        ocr::read_grid(&decoded_image, |&d| d == sif::WHITE).unwrap_or_else(|_| {
+            decoded_image
+                .display(|&d| match d {
+                    sif::WHITE => '*',
+                    _ => ' ',
                 })
-                .join("")
-        )
-    });
-}
-
-struct Image {
-    layers: Vec<Vec<u32>>,
-    width: usize,
-    height: usize,
+                .to_string()
+        }),
+    )
 }
 
-impl Image {
-    fn layer(&self, layer: usize) -> &Vec<u32> {
-        &self.layers[layer]
+/// `export=<file>` writes the decoded image as PBM, PGM or PPM, depending on
+/// the file's extension.
+pub fn report(image: &Image, params: &Params) -> Result<String, String> {
+    if let Some(path) = params.get::<String>("export")? {
+        let format = Path::new(&path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(Netpbm::from_extension)
+            .ok_or_else(|| format!("cannot tell the image format of {}", path))?;
+        let decoded_image = image.decode(params.get_or("fallback", sif::BLACK)?);
+        fs::write(&path, format.export(&decoded_image))
+            .map_err(|e| format!("cannot write {}: {}", path, e))?;
     }
-
-    fn decode(&self) -> Vec<u32> {
-        (0..self.width * self.height)
-            .map(|pixel| {
-                let mut layer = 0;
-                while layer < self.layers.len() && self.layers[layer][pixel] == 2 {
-                    layer += 1;
-                }
-                self.layers[layer][pixel]
-            })
-            .collect()
-    }
-}
-
-fn count(value: u32, layer: &[u32]) -> usize {
-    layer.iter().filter(|pixel| **pixel == value).count()
+    Ok(String::new())
 }
 
-fn load_data(test: bool) -> Image {
-    let width = if test { 3 } else { 25 };
-    let height = if test { 2 } else { 6 };
-
-    let layers = aoc2019::load_data(8, test)
-        .trim()
-        .as_bytes()
-        .chunks(width * height)
-        .map(|slice| str::from_utf8(slice).expect("Oops..").to_owned())
-        .map(|layer| layer.chars().map(|d| d.to_digit(10).unwrap()).collect())
-        .collect();
+pub fn parse(input: &Input) -> Result<Image, String> {
+    let width = input.params().get_or("width", 25)?;
+    let height = input.params().get_or("height", 6)?;
 
-    Image {
-        layers,
-        width,
-        height,
-    }
+    Image::parse(input.data(), width, height).map_err(|e| format!("invalid image: {}", e))
 }
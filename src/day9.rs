@@ -1,22 +1,26 @@
-use crate::intcode::Intcode;
+use itertools::Itertools;
 
-pub fn solve_part_one(test: bool) {
-    let mut program = load_data(test);
+use aoc2019::{Input, Params, intcode::Intcode};
+
+pub fn solve_part_one(program: &Intcode, _params: &Params) -> Result<String, String> {
+    let mut program = program.clone();
     program.add_input(1);
     program.execute();
+    let mut outputs = Vec::new();
     while let Some(output) = program.next_output() {
-        println!("Part 1: {}", output);
+        outputs.push(output);
         program.execute();
     }
+    Ok(outputs.iter().join(","))
 }
 
-pub fn solve_part_two(test: bool) {
-    let mut program = load_data(test);
+pub fn solve_part_two(program: &Intcode, _params: &Params) -> Result<String, String> {
+    let mut program = program.clone();
     program.add_input(2);
     program.execute();
-    println!("Part 2: {}", program.next_output().unwrap());
+    Ok(program.next_output().unwrap().to_string())
 }
 
-fn load_data(test: bool) -> Intcode {
-    Intcode::from(aoc2019::load_data(9, test).trim())
+pub fn parse(input: &Input) -> Result<Intcode, String> {
+    Ok(Intcode::from(input.data().trim()))
 }
@@ -1,41 +1,188 @@
-use crate::coordinates::Coordinates;
+use std::{error, fmt, str::FromStr};
 
-#[derive(Debug, Default)]
+use crate::{coordinates::Coordinates, scalar::Scalar};
+
+/// Heading on a screen-space grid, where `Up` decreases y.
+///
+/// Variants are listed clockwise from `Up`, diagonals included.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Direction {
     #[default]
     Up,
+    UpRight,
     Right,
+    DownRight,
     Down,
+    DownLeft,
     Left,
+    UpLeft,
 }
 
+const ALL: [Direction; 8] = [
+    Direction::Up,
+    Direction::UpRight,
+    Direction::Right,
+    Direction::DownRight,
+    Direction::Down,
+    Direction::DownLeft,
+    Direction::Left,
+    Direction::UpLeft,
+];
+
 impl Direction {
+    /// Every direction, clockwise from `Up`.
+    pub fn all() -> impl Iterator<Item = Direction> {
+        ALL.into_iter()
+    }
+
+    /// Up, right, down and left.
+    pub fn orthogonal() -> impl Iterator<Item = Direction> {
+        ALL.into_iter().step_by(2)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.index() % 2 == 1
+    }
+
     pub fn turn_left(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Left,
-            Direction::Right => Direction::Up,
-            Direction::Down => Direction::Right,
-            Direction::Left => Direction::Down,
-        }
+        self.turn(-1)
     }
 
     pub fn turn_right(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        }
+        self.turn(1)
     }
 
-    pub fn next(&self, coordinates: &Coordinates) -> Coordinates {
-        let velocity = match self {
-            Direction::Up => Coordinates::new(0, 1),
-            Direction::Right => Coordinates::new(1, 0),
-            Direction::Down => Coordinates::new(0, -1),
-            Direction::Left => Coordinates::new(-1, 0),
+    /// Turns by `quarter_turns` steps of 90°, clockwise for positive values.
+    pub fn turn(&self, quarter_turns: i32) -> Direction {
+        self.turn_eighths(2 * quarter_turns)
+    }
+
+    /// Turns by `eighth_turns` steps of 45°, clockwise for positive values.
+    pub fn turn_eighths(&self, eighth_turns: i32) -> Direction {
+        ALL[(self.index() as i32 + eighth_turns).rem_euclid(8) as usize]
+    }
+
+    pub fn reverse(&self) -> Direction {
+        self.turn(2)
+    }
+
+    /// Unit step in this direction; diagonal steps move along both axes.
+    pub fn unit<T: Scalar>(&self) -> Coordinates<T> {
+        let (x, y) = match self {
+            Direction::Up => (0, -1),
+            Direction::UpRight => (1, -1),
+            Direction::Right => (1, 0),
+            Direction::DownRight => (1, 1),
+            Direction::Down => (0, 1),
+            Direction::DownLeft => (-1, 1),
+            Direction::Left => (-1, 0),
+            Direction::UpLeft => (-1, -1),
         };
+        Coordinates::new(T::from(x).unwrap(), T::from(y).unwrap())
+    }
+
+    pub fn next<T: Scalar>(&self, coordinates: &Coordinates<T>) -> Coordinates<T> {
+        coordinates + &self.unit()
+    }
+
+    /// Moves `distance` steps in this direction.
+    pub fn step<T: Scalar>(&self, coordinates: &Coordinates<T>, distance: T) -> Coordinates<T> {
+        coordinates + &(&self.unit() * distance)
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl<T: Scalar> TryFrom<Coordinates<T>> for Direction {
+    type Error = ParseDirectionError;
 
-        coordinates + &velocity
+    /// Direction of a unit step, including diagonal ones.
+    fn try_from(value: Coordinates<T>) -> Result<Self, Self::Error> {
+        Direction::all()
+            .find(|direction| direction.unit::<T>() == value)
+            .ok_or_else(|| ParseDirectionError(value.to_string()))
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = ParseDirectionError;
+
+    /// Parses `U`, `R`, `D` and `L`, or the compass points `N`, `E`, `S` and `W`.
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value.to_ascii_uppercase() {
+            'U' | 'N' => Ok(Direction::Up),
+            'R' | 'E' => Ok(Direction::Right),
+            'D' | 'S' => Ok(Direction::Down),
+            'L' | 'W' => Ok(Direction::Left),
+            _ => Err(ParseDirectionError(value.to_string())),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseDirectionError;
+
+    /// Parses a single letter accepted by `TryFrom<char>`, or two of them for a
+    /// diagonal, such as `NE` or `UL`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseDirectionError(s.to_owned());
+        let mut chars = s.chars();
+        let first = chars.next().ok_or_else(error)?;
+        let first = Direction::try_from(first).map_err(|_| error())?;
+
+        match (chars.next(), chars.next()) {
+            (None, _) => Ok(first),
+            (Some(second), None) => {
+                let second = Direction::try_from(second).map_err(|_| error())?;
+                Direction::try_from(second.next(&first.unit::<i32>()))
+                    .ok()
+                    .filter(Direction::is_diagonal)
+                    .ok_or_else(error)
+            }
+            _ => Err(error()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseDirectionError(String);
+
+impl fmt::Display for ParseDirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid direction: {}", self.0)
+    }
+}
+
+impl error::Error for ParseDirectionError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_turn() {
+        assert_eq!(Direction::Left, Direction::Up.turn_left());
+        assert_eq!(Direction::Down, Direction::Left.turn(-1));
+        assert_eq!(Direction::DownLeft, Direction::UpRight.reverse());
+        assert_eq!(Direction::UpRight, Direction::Up.turn_eighths(9));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Ok(Direction::Up), "N".parse());
+        assert_eq!(Ok(Direction::Left), Direction::try_from('L'));
+        assert_eq!(Ok(Direction::DownRight), "SE".parse());
+        assert!("NS".parse::<Direction>().is_err());
+        assert!("X".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_unit() {
+        for direction in Direction::all() {
+            assert_eq!(Ok(direction), Direction::try_from(direction.unit::<i64>()));
+        }
+        assert_eq!(4, Direction::orthogonal().count());
     }
 }
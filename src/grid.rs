@@ -0,0 +1,324 @@
+use std::{collections::HashMap, error, fmt};
+
+use crate::coordinates::{Bounds, Coordinates};
+
+/// Dense grid of cells, stored row by row, with `(0, 0)` as its top-left cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "A {}x{} grid needs {} cells",
+            width,
+            height,
+            width * height
+        );
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses one row per line, mapping each character to a cell. Every row
+    /// must be as wide as the first one.
+    pub fn parse(text: &str, mut cell: impl FnMut(char) -> T) -> Result<Self, GridError> {
+        let mut width = 0;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for line in text.lines().filter(|line| !line.is_empty()) {
+            let row_start = cells.len();
+            cells.extend(line.chars().map(&mut cell));
+            let row_width = cells.len() - row_start;
+            if height == 0 {
+                width = row_width;
+            } else if row_width != width {
+                return Err(GridError::RowWidth {
+                    row: height,
+                    expected: width,
+                    found: row_width,
+                });
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds::new(
+            Coordinates::origin(),
+            Coordinates::new(self.width as i32 - 1, self.height as i32 - 1),
+        )
+    }
+
+    pub fn contains(&self, coordinates: &Coordinates) -> bool {
+        self.index(coordinates).is_some()
+    }
+
+    pub fn get(&self, coordinates: &Coordinates) -> Option<&T> {
+        self.index(coordinates).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, coordinates: &Coordinates) -> Option<&mut T> {
+        self.index(coordinates).map(|index| &mut self.cells[index])
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Iterates over the cells in reading order along with their coordinates.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, &T)> {
+        self.cells.iter().enumerate().map(|(index, cell)| {
+            let coordinates =
+                Coordinates::new((index % self.width) as i32, (index / self.width) as i32);
+            (coordinates, cell)
+        })
+    }
+
+    /// Orthogonal neighbours of `coordinates` that lie inside the grid.
+    pub fn neighbours(&self, coordinates: &Coordinates) -> impl Iterator<Item = Coordinates> {
+        let bounds = self.bounds();
+        coordinates
+            .neighbours_4()
+            .filter(move |neighbour| bounds.contains(neighbour))
+    }
+
+    /// Renders the grid, mapping each cell to a character with `glyph`.
+    pub fn display<F: Fn(&T) -> char>(&self, glyph: F) -> Render<'_, Self, F> {
+        Render { grid: self, glyph }
+    }
+
+    fn index(&self, coordinates: &Coordinates) -> Option<usize> {
+        let x = usize::try_from(coordinates.get_x()).ok()?;
+        let y = usize::try_from(coordinates.get_y()).ok()?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+}
+
+/// Unbounded grid storing only the cells that have been set, keeping track of
+/// the area they cover.
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Coordinates, T>,
+    bounds: Option<Bounds>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, coordinates: &Coordinates) -> Option<&T> {
+        self.cells.get(coordinates)
+    }
+
+    pub fn insert(&mut self, coordinates: Coordinates, value: T) -> Option<T> {
+        match &mut self.bounds {
+            Some(bounds) => bounds.include(&coordinates),
+            None => self.bounds = Some(Bounds::from_point(coordinates)),
+        }
+        self.cells.insert(coordinates, value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Smallest rectangle containing every cell set so far.
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Coordinates, &T)> {
+        self.cells.iter()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.values()
+    }
+
+    /// Orthogonal neighbours of `coordinates`, whether they are set or not.
+    pub fn neighbours(&self, coordinates: &Coordinates) -> impl Iterator<Item = Coordinates> {
+        coordinates.neighbours_4()
+    }
+
+    /// Renders the area covered by the grid, mapping each cell, set or not, to a
+    /// character with `glyph`.
+    pub fn display<F: Fn(Option<&T>) -> char>(&self, glyph: F) -> Render<'_, Self, F> {
+        Render { grid: self, glyph }
+    }
+}
+
+/// Text rendering of a grid, one line per row.
+pub struct Render<'a, G, F> {
+    grid: &'a G,
+    glyph: F,
+}
+
+impl<T, F: Fn(&T) -> char> fmt::Display for Render<'_, Grid<T>, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.grid.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", (self.glyph)(cell))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T, F: Fn(Option<&T>) -> char> fmt::Display for Render<'_, SparseGrid<T>, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(bounds) = self.grid.bounds() else {
+            return Ok(());
+        };
+
+        for y in bounds.min.get_y()..=bounds.max.get_y() {
+            if y > bounds.min.get_y() {
+                writeln!(f)?;
+            }
+            for x in bounds.min.get_x()..=bounds.max.get_x() {
+                let cell = self.grid.get(&Coordinates::new(x, y));
+                write!(f, "{}", (self.glyph)(cell))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Error returned when text does not describe a rectangular grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GridError {
+    /// A row is not as wide as the first one.
+    RowWidth {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RowWidth {
+                row,
+                expected,
+                found,
+            } => write!(f, "row {} has {} cells instead of {}", row, found, expected),
+        }
+    }
+}
+
+impl error::Error for GridError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse(".#.\n##.\n", |c| c == '#').unwrap();
+        assert_eq!(3, grid.width());
+        assert_eq!(2, grid.height());
+        assert_eq!(Some(&true), grid.get(&Coordinates::new(1, 0)));
+        assert_eq!(None, grid.get(&Coordinates::new(3, 0)));
+        assert_eq!(&[true, true, false], grid.row(1));
+        assert_eq!(vec![&true, &true], grid.column(1).collect::<Vec<_>>());
+        assert_eq!(
+            ".#.\n##.",
+            grid.display(|&c| if c { '#' } else { '.' }).to_string()
+        );
+        assert_eq!(
+            Err(GridError::RowWidth {
+                row: 1,
+                expected: 3,
+                found: 2
+            }),
+            Grid::parse(".#.\n#.\n", |c| c == '#')
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(2, 2, 0);
+        let neighbours: Vec<Coordinates> = grid.neighbours(&Coordinates::origin()).collect();
+        assert_eq!(
+            vec![Coordinates::new(1, 0), Coordinates::new(0, 1)],
+            neighbours
+        );
+    }
+
+    #[test]
+    fn test_sparse_bounds() {
+        let mut grid = SparseGrid::new();
+        grid.insert(Coordinates::new(-1, 2), 'a');
+        grid.insert(Coordinates::new(1, 0), 'b');
+        assert_eq!(
+            Some(Bounds::new(Coordinates::new(-1, 0), Coordinates::new(1, 2))),
+            grid.bounds()
+        );
+        assert_eq!(
+            "..b\n...\na..",
+            grid.display(|c| *c.unwrap_or(&'.')).to_string()
+        );
+    }
+}
@@ -1,10 +1,260 @@
-use std::fs;
-
-pub fn load_data(day: u8, test: bool) -> String {
-    let path = if test {
-        format!("rsc/day{}_test.txt", day)
-    } else {
-        format!("rsc/day{}.txt", day)
-    };
-    fs::read_to_string(path).unwrap()
+pub mod coordinates;
+pub mod cycle;
+pub mod direction;
+pub mod grid;
+pub mod intcode;
+pub mod nbody;
+pub mod ocr;
+pub mod orbits;
+pub mod scalar;
+pub mod sif;
+pub mod vector;
+
+use std::{
+    collections::HashMap,
+    env, error, fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Environment variable overriding the directory puzzle inputs are read from.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+const DEFAULT_INPUT_DIR: &str = "rsc";
+
+/// Where the input of a day is read from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// The puzzle input, `dayN.txt`.
+    Puzzle,
+    /// An example input, `dayN_test.txt`, or `dayN_test_<name>.txt` when named.
+    Example(Option<String>),
+    /// An explicit file path, or the standard input for `-`.
+    File(PathBuf),
+}
+
+/// Puzzle parameters, such as image dimensions or step counts, that differ
+/// between the puzzle input and the examples.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Params {
+    values: HashMap<String, String>,
+}
+
+impl Params {
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.values.insert(key.to_owned(), value.to_owned());
+    }
+
+    /// Value of the parameter `key`, if it is set.
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<Option<T>, String>
+    where
+        T::Err: fmt::Display,
+    {
+        self.values
+            .get(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|e| format!("invalid value '{}' for parameter {}: {}", value, key, e))
+            })
+            .transpose()
+    }
+
+    pub fn get_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, String>
+    where
+        T::Err: fmt::Display,
+    {
+        Ok(self.get(key)?.unwrap_or(default))
+    }
+
+    /// Keys that are not among `known`, in alphabetical order.
+    pub fn unknown(&self, known: &[&str]) -> Vec<&str> {
+        let mut unknown: Vec<&str> = self
+            .values
+            .keys()
+            .map(String::as_str)
+            .filter(|key| !known.contains(key))
+            .collect();
+        unknown.sort_unstable();
+        unknown
+    }
+}
+
+impl Extend<(String, String)> for Params {
+    fn extend<I: IntoIterator<Item = (String, String)>>(&mut self, iter: I) {
+        self.values.extend(iter);
+    }
+}
+
+impl FromStr for Params {
+    type Err = String;
+
+    /// Parses one `key=value` pair per line, ignoring blank lines and `#` comments.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(parse_param)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { values })
+    }
+}
+
+/// Parses a `key=value` parameter.
+pub fn parse_param(param: &str) -> Result<(String, String), String> {
+    match param.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_owned(), value.trim().to_owned()))
+        }
+        _ => Err(format!("invalid parameter '{}', expected key=value", param)),
+    }
+}
+
+/// Raw puzzle input handed to the solvers.
+#[derive(Clone, Debug)]
+pub struct Input {
+    data: String,
+    params: Params,
+}
+
+impl Input {
+    pub fn new(data: String, params: Params) -> Self {
+        Self { data, params }
+    }
+
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    pub fn params(&self) -> &Params {
+        &self.params
+    }
+
+    pub fn params_mut(&mut self) -> &mut Params {
+        &mut self.params
+    }
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    File(PathBuf, io::Error),
+    Stdin(io::Error),
+    Params(PathBuf, String),
+}
+
+impl LoadError {
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            LoadError::File(_, error) | LoadError::Stdin(error) => {
+                error.kind() == io::ErrorKind::NotFound
+            }
+            LoadError::Params(_, _) => false,
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::File(path, error) => {
+                write!(f, "could not read input {}: {}", path.display(), error)
+            }
+            LoadError::Stdin(error) => write!(f, "could not read input from stdin: {}", error),
+            LoadError::Params(path, error) => write!(f, "{} in {}", error, path.display()),
+        }
+    }
+}
+
+impl error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            LoadError::File(_, error) | LoadError::Stdin(error) => Some(error),
+            LoadError::Params(_, _) => None,
+        }
+    }
+}
+
+/// Reads puzzle inputs from an input directory.
+#[derive(Clone, Debug)]
+pub struct Loader {
+    directory: PathBuf,
+}
+
+impl Default for Loader {
+    fn default() -> Self {
+        let directory = env::var_os(INPUT_DIR_VAR).unwrap_or_else(|| DEFAULT_INPUT_DIR.into());
+        Self::new(directory)
+    }
+}
+
+impl Loader {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Path the input is read from, or `None` for the standard input.
+    pub fn path(&self, day: u8, source: &Source) -> Option<PathBuf> {
+        match source {
+            Source::Puzzle => Some(self.directory.join(format!("day{}.txt", day))),
+            Source::Example(None) => Some(self.directory.join(format!("day{}_test.txt", day))),
+            Source::Example(Some(name)) => {
+                Some(self.directory.join(format!("day{}_test_{}.txt", day, name)))
+            }
+            Source::File(path) if path.as_os_str() == "-" => None,
+            Source::File(path) => Some(path.clone()),
+        }
+    }
+
+    /// Reads the input, along with its parameters from the `.params` file next
+    /// to it when there is one.
+    pub fn load(&self, day: u8, source: &Source) -> Result<Input, LoadError> {
+        let Some(path) = self.path(day, source) else {
+            let mut data = String::new();
+            io::stdin()
+                .read_to_string(&mut data)
+                .map_err(LoadError::Stdin)?;
+            return Ok(Input::new(data, Params::default()));
+        };
+
+        let data = fs::read_to_string(&path).map_err(|e| LoadError::File(path.clone(), e))?;
+
+        let params_path = path.with_extension("params");
+        let params = match fs::read_to_string(&params_path) {
+            Ok(params) => params
+                .parse()
+                .map_err(|e| LoadError::Params(params_path, e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Params::default(),
+            Err(e) => return Err(LoadError::File(params_path, e)),
+        };
+
+        Ok(Input::new(data, params))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_params() {
+        let params: Params = "width=25\n# comment\nheight=abc\n".parse().unwrap();
+        assert_eq!(Ok(Some(25)), params.get::<usize>("width"));
+        assert_eq!(Ok(6), params.get_or::<usize>("depth", 6));
+        assert_eq!(
+            Err(String::from(
+                "invalid value 'abc' for parameter height: invalid digit found in string"
+            )),
+            params.get_or::<usize>("height", 6)
+        );
+        assert_eq!(vec!["height"], params.unknown(&["width"]));
+    }
 }
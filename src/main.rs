@@ -1,4 +1,6 @@
-mod coordinates;
+#[cfg(test)]
+mod answers;
+mod bench;
 mod day1;
 mod day2;
 mod day3;
@@ -13,80 +15,260 @@ mod day11;
 mod day12;
 mod day13;
 mod day14;
-mod direction;
-mod intcode;
-mod vec3d;
 
-use clap::Parser;
+use std::{fs, path::PathBuf, process};
+
+use aoc2019::{INPUT_DIR_VAR, Input, LoadError, Loader, Source, parse_param};
+use bench::{Report, Timings};
+use clap::{Parser, Subcommand, builder::RangedU64ValueParser};
+
+type Solver = fn(&Input) -> Result<String, String>;
+
+struct Day {
+    part_one: Solver,
+    part_two: Solver,
+    /// Visualisations, exports and queries asked for with parameters, kept out
+    /// of the solvers so that benchmarks only time the answers.
+    report: Option<Solver>,
+    bench: fn(&Input, usize) -> Result<Timings, String>,
+    /// Parameters the day reads.
+    params: &'static [&'static str],
+}
+
+macro_rules! day {
+    ($day:ident $(, [$($param:literal),* $(,)?])?) => {
+        day!(@ $day, [$($($param),*)?], None)
+    };
+    ($day:ident, [$($param:literal),* $(,)?], report) => {
+        day!(
+            @ $day,
+            [$($param),*],
+            Some(|input| $day::report(&$day::parse(input)?, input.params()))
+        )
+    };
+    (@ $day:ident, [$($param:literal),*], $report:expr) => {
+        Day {
+            part_one: |input| $day::solve_part_one(&$day::parse(input)?, input.params()),
+            part_two: |input| $day::solve_part_two(&$day::parse(input)?, input.params()),
+            report: $report,
+            bench: |input, runs| {
+                let data = $day::parse(input)?;
+                // Fail on invalid parameters before timing anything.
+                $day::solve_part_one(&data, input.params())?;
+                $day::solve_part_two(&data, input.params())?;
+                Ok(Timings {
+                    parse: bench::measure(runs, || $day::parse(input)),
+                    part_one: bench::measure(runs, || $day::solve_part_one(&data, input.params())),
+                    part_two: bench::measure(runs, || $day::solve_part_two(&data, input.params())),
+                })
+            },
+            params: &[$($param),*],
+        }
+    };
+}
+
+const DAYS: [Day; 14] = [
+    day!(day1),
+    day!(day2, ["target", "patch", "noun", "verb"]),
+    day!(day3, ["intersections", "render", "svg"], report),
+    day!(day4, ["rules", "nth"]),
+    day!(day5),
+    day!(
+        day6,
+        [
+            "tree",
+            "dot",
+            "highlight",
+            "collapse",
+            "from",
+            "to",
+            "route",
+            "within",
+            "diameter"
+        ],
+        report
+    ),
+    day!(
+        day7,
+        ["stages", "phases", "topology", "signal", "trace"],
+        report
+    ),
+    day!(day8, ["width", "height", "fallback", "export"], report),
+    day!(day9),
+    day!(
+        day10,
+        [
+            "heatmap", "visible", "blocker", "start", "rotation", "order", "nth"
+        ],
+        report
+    ),
+    day!(day11),
+    day!(day12, ["steps"]),
+    day!(day13),
+    day!(day14),
+];
 
 #[derive(Debug, Parser)]
-#[command(about)]
+#[command(about, subcommand_negates_reqs = true)]
 struct Args {
-    #[arg(short, long)]
-    test: bool,
-    #[arg(short, long)]
-    day: u8,
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[arg(short, long, required = true)]
+    day: Option<u8>,
+    /// Use the example input, or the named example `dayN_test_<NAME>.txt`
+    #[arg(
+        short,
+        long,
+        value_name = "NAME",
+        conflicts_with = "input",
+        global = true
+    )]
+    test: Option<Option<String>>,
+    /// Read the input from FILE instead of the input directory, `-` for stdin
+    #[arg(short, long, value_name = "FILE", global = true)]
+    input: Option<PathBuf>,
+    /// Directory containing the `dayN.txt` inputs
+    #[arg(long, env = INPUT_DIR_VAR, default_value = "rsc", global = true)]
+    input_dir: PathBuf,
+    /// Override a puzzle parameter, e.g. `width=25` for day 8
+    #[arg(
+        short,
+        long = "param",
+        value_name = "KEY=VALUE",
+        value_parser = parse_param,
+        global = true
+    )]
+    params: Vec<(String, String)>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Time the parsing and both parts of every day
+    Bench {
+        /// Only benchmark the given day
+        #[arg(short, long)]
+        day: Option<u8>,
+        /// Number of times each step is run
+        #[arg(
+            short,
+            long,
+            default_value_t = 10,
+            value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        runs: usize,
+        /// Write a JSON summary of the timings to FILE
+        #[arg(long, value_name = "FILE")]
+        json: Option<PathBuf>,
+    },
+}
+
+impl Args {
+    fn source(&self) -> Source {
+        match (&self.input, &self.test) {
+            (Some(path), _) => Source::File(path.clone()),
+            (None, Some(name)) => Source::Example(name.clone()),
+            (None, None) => Source::Puzzle,
+        }
+    }
+
+    fn load(&self, day: u8) -> Result<Input, LoadError> {
+        let mut input = Loader::new(&self.input_dir).load(day, &self.source())?;
+        input.params_mut().extend(self.params.iter().cloned());
+        Ok(input)
+    }
 }
 
 fn main() {
     let args = Args::parse();
-    match args.day {
-        1 => {
-            day1::solve_part_one(args.test);
-            day1::solve_part_two(args.test);
-        }
-        2 => {
-            day2::solve_part_one(args.test);
-            day2::solve_part_two(args.test);
-        }
-        3 => {
-            day3::solve_part_one(args.test);
-            day3::solve_part_two(args.test);
-        }
-        4 => {
-            day4::solve_part_one(args.test);
-            day4::solve_part_two(args.test);
-        }
-        5 => {
-            day5::solve_part_one(args.test);
-            day5::solve_part_two(args.test);
-        }
-        6 => {
-            day6::solve_part_one(args.test);
-            day6::solve_part_two(args.test);
-        }
-        7 => {
-            day7::solve_part_one(args.test);
-            day7::solve_part_two(args.test);
-        }
-        8 => {
-            day8::solve_part_one(args.test);
-            day8::solve_part_two(args.test);
-        }
-        9 => {
-            day9::solve_part_one(args.test);
-            day9::solve_part_two(args.test);
-        }
-        10 => {
-            day10::solve_part_one(args.test);
-            day10::solve_part_two(args.test);
-        }
-        11 => {
-            day11::solve_part_one(args.test);
-            day11::solve_part_two(args.test);
-        }
-        12 => {
-            day12::solve_part_one(args.test);
-            day12::solve_part_two(args.test);
-        }
-        13 => {
-            day13::solve_part_one(args.test);
-            day13::solve_part_two(args.test);
-        }
-        14 => {
-            day14::solve_part_one(args.test);
-            day14::solve_part_two(args.test);
+    match &args.command {
+        Some(Command::Bench { day, runs, json }) => run_bench(&args, *day, *runs, json.as_ref()),
+        None => solve(&args, args.day.expect("--day is required")),
+    }
+}
+
+fn solve(args: &Args, day: u8) {
+    let Some(solvers) = get_day(day) else {
+        eprintln!("Day {} is not solved yet", day);
+        process::exit(1);
+    };
+
+    let input = args.load(day).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    });
+    warn_unknown_params(day, solvers, &input);
+
+    let fail = |e: String| -> ! {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    };
+    for (part, solver) in [(1, solvers.part_one), (2, solvers.part_two)] {
+        print_answer(part, &solver(&input).unwrap_or_else(|e| fail(e)));
+    }
+    if let Some(report) = solvers.report {
+        eprint!("{}", report(&input).unwrap_or_else(|e| fail(e)));
+    }
+}
+
+fn run_bench(args: &Args, day: Option<u8>, runs: usize, json: Option<&PathBuf>) {
+    let days = match day {
+        Some(day) => vec![day],
+        None => (1..=DAYS.len() as u8).collect(),
+    };
+
+    let mut reports = Vec::new();
+    for day in days {
+        let Some(solvers) = get_day(day) else {
+            eprintln!("Day {} is not solved yet", day);
+            continue;
+        };
+        let input = match args.load(day) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Day {} skipped: {}", day, e);
+                continue;
+            }
+        };
+
+        warn_unknown_params(day, solvers, &input);
+
+        let timings = match (solvers.bench)(&input, runs) {
+            Ok(timings) => timings,
+            Err(e) => {
+                eprintln!("Day {} skipped: {}", day, e);
+                continue;
+            }
+        };
+        let report = Report { day, runs, timings };
+        println!("{}", report);
+        reports.push(report);
+    }
+
+    if let Some(path) = json {
+        let summary = serde_json::to_string_pretty(&reports).expect("Could not serialize timings");
+        if let Err(e) = fs::write(path, summary) {
+            eprintln!("Error: could not write {}: {}", path.display(), e);
+            process::exit(1);
         }
-        _ => {}
+    }
+}
+
+fn get_day(day: u8) -> Option<&'static Day> {
+    DAYS.get(usize::from(day).checked_sub(1)?)
+}
+
+/// Warns about parameters the day does not read, which are most likely typos.
+fn warn_unknown_params(day: u8, solvers: &Day, input: &Input) {
+    for key in input.params().unknown(solvers.params) {
+        eprintln!("Warning: day {} has no parameter {}", day, key);
+    }
+}
+
+fn print_answer(part: u8, answer: &str) {
+    if answer.contains('\n') {
+        println!("Part {}:", part);
+        println!("{}", answer);
+    } else {
+        println!("Part {}: {}", part, answer);
     }
 }
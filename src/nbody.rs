@@ -0,0 +1,240 @@
+use std::io::{self, Write};
+
+use crate::vector::{self, VecN};
+
+/// Body of an N-body system, moving in `N` dimensions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Body<const N: usize> {
+    pub position: VecN<N>,
+    pub velocity: VecN<N>,
+}
+
+impl<const N: usize> Body<N> {
+    /// Body at rest at `position`.
+    pub fn new(position: VecN<N>) -> Self {
+        Self {
+            position,
+            velocity: VecN::zero(),
+        }
+    }
+
+    pub fn potential_energy(&self) -> i64 {
+        self.position.manhattan()
+    }
+
+    pub fn kinetic_energy(&self) -> i64 {
+        self.velocity.manhattan()
+    }
+
+    /// Total energy: the product of the potential and kinetic energies.
+    pub fn energy(&self) -> i64 {
+        self.potential_energy() * self.kinetic_energy()
+    }
+}
+
+/// Law giving the change in velocity a body at `position` undergoes because of
+/// a body at `other`.
+pub trait Gravity<const N: usize> {
+    fn pull(&self, position: &VecN<N>, other: &VecN<N>) -> VecN<N>;
+}
+
+/// The puzzle's law: each axis moves by one unit towards the other body.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Signum;
+
+impl<const N: usize> Gravity<N> for Signum {
+    fn pull(&self, position: &VecN<N>, other: &VecN<N>) -> VecN<N> {
+        (other - position).signum()
+    }
+}
+
+impl<const N: usize, F> Gravity<N> for F
+where
+    F: Fn(&VecN<N>, &VecN<N>) -> VecN<N>,
+{
+    fn pull(&self, position: &VecN<N>, other: &VecN<N>) -> VecN<N> {
+        self(position, other)
+    }
+}
+
+/// Bodies attracting each other step by step under a gravity law.
+#[derive(Clone, Debug)]
+pub struct MoonSystem<const N: usize, G = Signum> {
+    bodies: Vec<Body<N>>,
+    gravity: G,
+    steps: usize,
+}
+
+impl<const N: usize> MoonSystem<N> {
+    /// System of bodies at rest at `positions`, under the puzzle's gravity.
+    pub fn new(positions: &[VecN<N>]) -> Self {
+        Self::with_gravity(positions, Signum)
+    }
+}
+
+impl<const N: usize, G: Gravity<N>> MoonSystem<N, G> {
+    pub fn with_gravity(positions: &[VecN<N>], gravity: G) -> Self {
+        Self {
+            bodies: positions.iter().copied().map(Body::new).collect(),
+            gravity,
+            steps: 0,
+        }
+    }
+
+    pub fn bodies(&self) -> &[Body<N>] {
+        &self.bodies
+    }
+
+    /// Number of steps simulated so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Applies gravity between every pair of bodies, then moves them.
+    pub fn step(&mut self) {
+        for i in 0..self.bodies.len() {
+            let mut velocity = self.bodies[i].velocity;
+            for (j, other) in self.bodies.iter().enumerate() {
+                if i != j {
+                    velocity += self.gravity.pull(&self.bodies[i].position, &other.position);
+                }
+            }
+            self.bodies[i].velocity = velocity;
+        }
+
+        for body in self.bodies.iter_mut() {
+            body.position += body.velocity;
+        }
+        self.steps += 1;
+    }
+
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Energy of each body.
+    pub fn energies(&self) -> Vec<i64> {
+        self.bodies.iter().map(Body::energy).collect()
+    }
+
+    pub fn energy(&self) -> i64 {
+        self.bodies.iter().map(Body::energy).sum()
+    }
+
+    /// Writes `steps` more steps of the trajectory as CSV, one line per body and
+    /// step, starting with the current state.
+    pub fn write_csv<W: Write>(&mut self, writer: &mut W, steps: usize) -> io::Result<()> {
+        let axes: Vec<String> = (0..N).map(vector::axis).collect();
+        let velocities: Vec<String> = axes.iter().map(|axis| format!("v{}", axis)).collect();
+        writeln!(
+            writer,
+            "step,body,{},{}",
+            axes.join(","),
+            velocities.join(",")
+        )?;
+
+        for step in 0..=steps {
+            if step > 0 {
+                self.step();
+            }
+            for (index, body) in self.bodies.iter().enumerate() {
+                let values: Vec<String> = body
+                    .position
+                    .components()
+                    .iter()
+                    .chain(body.velocity.components())
+                    .map(|value| value.to_string())
+                    .collect();
+                writeln!(writer, "{},{},{}", self.steps, index, values.join(","))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<const N: usize, G: Gravity<N> + Clone> MoonSystem<N, G> {
+    /// Iterates over the states of the system, starting with the current one,
+    /// without advancing it.
+    pub fn history(&self) -> History<N, G> {
+        History {
+            system: self.clone(),
+            started: false,
+        }
+    }
+}
+
+/// Endless sequence of the states of a [`MoonSystem`].
+pub struct History<const N: usize, G> {
+    system: MoonSystem<N, G>,
+    started: bool,
+}
+
+impl<const N: usize, G: Gravity<N>> Iterator for History<N, G> {
+    type Item = Vec<Body<N>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.started {
+            self.system.step();
+        }
+        self.started = true;
+        Some(self.system.bodies.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::vector::{Vec2D, Vec3D};
+
+    fn example() -> Vec<Vec3D> {
+        [
+            "<x=-1, y=0, z=2>",
+            "<x=2, y=-10, z=-7>",
+            "<x=4, y=-8, z=8>",
+            "<x=3, y=5, z=-1>",
+        ]
+        .iter()
+        .map(|line| line.parse().unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_energy() {
+        let mut system = MoonSystem::new(&example());
+        system.run(10);
+        assert_eq!(vec![36, 45, 80, 18], system.energies());
+        assert_eq!(179, system.energy());
+    }
+
+    #[test]
+    fn test_history() {
+        let system = MoonSystem::new(&example());
+        let state = system.history().nth(10).unwrap();
+        assert_eq!(Vec3D::new([2, 1, -3]), state[0].position);
+        assert_eq!(Vec3D::new([-3, -2, 1]), state[0].velocity);
+        assert_eq!(0, system.steps());
+    }
+
+    #[test]
+    fn test_custom_gravity() {
+        let repel = |position: &Vec2D, other: &Vec2D| (position - other).signum();
+        let mut system = MoonSystem::with_gravity(&[Vec2D::new([0, 0]), Vec2D::new([1, 0])], repel);
+        system.step();
+        assert_eq!(Vec2D::new([-1, 0]), system.bodies()[0].position);
+        assert_eq!(Vec2D::new([2, 0]), system.bodies()[1].position);
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut system = MoonSystem::new(&[Vec2D::new([0, 0]), Vec2D::new([2, 0])]);
+        let mut csv = Vec::new();
+        system.write_csv(&mut csv, 1).unwrap();
+        assert_eq!(
+            "step,body,x,y,vx,vy\n0,0,0,0,0,0\n0,1,2,0,0,0\n1,0,1,0,1,0\n1,1,1,0,-1,0\n",
+            String::from_utf8(csv).unwrap()
+        );
+    }
+}
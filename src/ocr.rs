@@ -0,0 +1,261 @@
+use std::{error, fmt, ops::Range};
+
+use crate::{
+    coordinates::Coordinates,
+    grid::{Grid, SparseGrid},
+};
+
+/// Letters of the 6 pixel high font, `#` for lit pixels.
+const FONT_6: [(char, &str); 18] = [
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// Letters of the 10 pixel high font, `#` for lit pixels.
+const FONT_10: [(char, &str); 15] = [
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+/// Reads the capital letters drawn by the cells of `grid` for which `lit` holds.
+pub fn read_grid<T>(grid: &Grid<T>, lit: impl Fn(&T) -> bool) -> Result<String, OcrError> {
+    let rows: Vec<Vec<bool>> = grid
+        .rows()
+        .map(|row| row.iter().map(&lit).collect())
+        .collect();
+    read(&rows)
+}
+
+/// Reads the capital letters drawn by the cells of `grid` for which `lit` holds,
+/// unset cells being dark.
+pub fn read_sparse<T>(grid: &SparseGrid<T>, lit: impl Fn(&T) -> bool) -> Result<String, OcrError> {
+    let Some(bounds) = grid.bounds() else {
+        return Err(OcrError::Empty);
+    };
+
+    let rows: Vec<Vec<bool>> = (bounds.min.get_y()..=bounds.max.get_y())
+        .map(|y| {
+            (bounds.min.get_x()..=bounds.max.get_x())
+                .map(|x| grid.get(&Coordinates::new(x, y)).is_some_and(&lit))
+                .collect()
+        })
+        .collect();
+    read(&rows)
+}
+
+/// Reads letters from rows of pixels, ignoring dark margins. Letters are told
+/// apart by the dark columns between them, except after letters such as `Y`
+/// that fill their whole cell: a run of lit columns is read as the longest
+/// letter it starts with, then the rest of the run.
+fn read(rows: &[Vec<bool>]) -> Result<String, OcrError> {
+    let lit_row = |row: &Vec<bool>| row.iter().any(|&pixel| pixel);
+    let (Some(top), Some(bottom)) = (
+        rows.iter().position(lit_row),
+        rows.iter().rposition(lit_row),
+    ) else {
+        return Err(OcrError::Empty);
+    };
+    let rows = &rows[top..=bottom];
+
+    let font: &[(char, &str)] = match rows.len() {
+        6 => &FONT_6,
+        10 => &FONT_10,
+        height => return Err(OcrError::Height(height)),
+    };
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let lit_column = |x: usize| rows.iter().any(|row| row.get(x) == Some(&true));
+    let glyph = |columns: Range<usize>| {
+        rows.iter()
+            .map(|row| {
+                columns
+                    .clone()
+                    .map(|x| match row.get(x) {
+                        Some(true) => '#',
+                        _ => '.',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let letter = |glyph: &str| {
+        font.iter()
+            .find(|(_, pattern)| *pattern == glyph)
+            .map(|(letter, _)| *letter)
+    };
+
+    let mut text = String::new();
+    let mut x = 0;
+    while x < width {
+        if !lit_column(x) {
+            x += 1;
+            continue;
+        }
+
+        let mut end = x;
+        while end < width && lit_column(end) {
+            end += 1;
+        }
+        while x < end {
+            let (next, found) = (x + 1..=end)
+                .rev()
+                .find_map(|next| letter(&glyph(x..next)).map(|found| (next, found)))
+                .ok_or_else(|| OcrError::UnknownGlyph(glyph(x..end)))?;
+            text.push(found);
+            x = next;
+        }
+    }
+
+    Ok(text)
+}
+
+/// Error returned when pixels cannot be read as letters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OcrError {
+    /// No pixel is lit.
+    Empty,
+    /// The lit pixels are not as high as any known font.
+    Height(usize),
+    /// A glyph, drawn with `#` and `.`, matches no letter.
+    UnknownGlyph(String),
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no letters to read"),
+            Self::Height(height) => write!(f, "no font is {} pixels high", height),
+            Self::UnknownGlyph(glyph) => write!(f, "unknown glyph\n{}", glyph),
+        }
+    }
+}
+
+impl error::Error for OcrError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_read_grid() {
+        let rows = [
+            "...................",
+            ".#..#.###..##..#...",
+            ".#..#..#..#..#.#...",
+            ".####..#..#..#.#...",
+            ".#..#..#..#..#.#...",
+            ".#..#..#..#..#.#...",
+            ".#..#.###..##..####",
+        ];
+        let grid = Grid::parse(&rows.join("\n"), |c| c == '#').unwrap();
+        assert_eq!(Ok(String::from("HIOL")), read_grid(&grid, |&lit| lit));
+
+        let grid = Grid::parse(&rows.join("\n").replace("..####", "..###."), |c| c == '#').unwrap();
+        assert_eq!(
+            Err(OcrError::UnknownGlyph(String::from(
+                "#..\n#..\n#..\n#..\n#..\n###"
+            ))),
+            read_grid(&grid, |&lit| lit)
+        );
+    }
+
+    #[test]
+    fn test_read_full_width() {
+        let rows = [
+            "#...##..#.###.#...#",
+            "#...##..#..#..#...#",
+            ".#.#.####..#...#.#.",
+            "..#..#..#..#....#..",
+            "..#..#..#..#....#..",
+            "..#..#..#.###...#..",
+        ];
+        let grid = Grid::parse(&rows.join("\n"), |c| c == '#').unwrap();
+        assert_eq!(Ok(String::from("YHIY")), read_grid(&grid, |&lit| lit));
+    }
+
+    #[test]
+    fn test_read_sparse() {
+        let mut grid = SparseGrid::new();
+        for (y, row) in FONT_10[14].1.lines().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                grid.insert(Coordinates::new(x as i32 - 3, y as i32 + 2), c == '#');
+            }
+        }
+        assert_eq!(Ok(String::from("Z")), read_sparse(&grid, |&lit| lit));
+    }
+}
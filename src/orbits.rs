@@ -0,0 +1,580 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error,
+    fmt::{self, Write},
+    str::FromStr,
+};
+
+/// Tree of objects orbiting each other, parsed from `A)B` lines meaning that
+/// `B` orbits `A`. Objects are interned and referred to by their index.
+#[derive(Clone, Debug)]
+pub struct OrbitMap {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    parents: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    depths: Vec<usize>,
+    subtree_sizes: Vec<usize>,
+    /// `ancestors[k][object]` is the ancestor `2^k` levels up, or the root of
+    /// the object's tree when it is closer than that.
+    ancestors: Vec<Vec<usize>>,
+}
+
+impl OrbitMap {
+    /// Builds the map from `(center, orbiter)` pairs.
+    pub fn new<'a>(
+        orbits: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, OrbitError> {
+        let mut names: Vec<String> = Vec::new();
+        let mut ids = HashMap::new();
+        let mut intern = |name: &str| -> usize {
+            *ids.entry(name.to_owned()).or_insert_with(|| {
+                names.push(name.to_owned());
+                names.len() - 1
+            })
+        };
+
+        let mut edges = Vec::new();
+        for (center, orbiter) in orbits {
+            edges.push((intern(center), intern(orbiter)));
+        }
+
+        let mut parents: Vec<Option<usize>> = vec![None; names.len()];
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); names.len()];
+        for (center, orbiter) in edges {
+            match parents[orbiter] {
+                Some(parent) if parent != center => {
+                    return Err(OrbitError::MultipleParents {
+                        object: names[orbiter].clone(),
+                        centers: [names[parent].clone(), names[center].clone()],
+                    });
+                }
+                Some(_) => (),
+                None => {
+                    parents[orbiter] = Some(center);
+                    children[center].push(orbiter);
+                }
+            }
+        }
+
+        // Visit the trees from their roots: objects on a cycle are never reached.
+        let mut order: Vec<usize> = (0..names.len())
+            .filter(|&object| parents[object].is_none())
+            .collect();
+        let mut depths = vec![0; names.len()];
+        let mut index = 0;
+        while index < order.len() {
+            let object = order[index];
+            for &child in children[object].iter() {
+                depths[child] = depths[object] + 1;
+                order.push(child);
+            }
+            index += 1;
+        }
+        if order.len() < names.len() {
+            let mut reached = vec![false; names.len()];
+            order.iter().for_each(|&object| reached[object] = true);
+            // Unreached objects only orbit unreached objects: following their
+            // centers ends up going round the cycle.
+            let mut object = (0..names.len())
+                .find(|&object| !reached[object])
+                .expect("Oops...");
+            let mut seen = vec![false; names.len()];
+            while !seen[object] {
+                seen[object] = true;
+                object = parents[object].expect("Oops...");
+            }
+            return Err(OrbitError::Cycle(names[object].clone()));
+        }
+
+        let mut subtree_sizes = vec![1; names.len()];
+        for &object in order.iter().rev() {
+            if let Some(parent) = parents[object] {
+                subtree_sizes[parent] += subtree_sizes[object];
+            }
+        }
+
+        let first: Vec<usize> = (0..names.len())
+            .map(|object| parents[object].unwrap_or(object))
+            .collect();
+        let max_depth = depths.iter().copied().max().unwrap_or(0);
+        let mut ancestors = vec![first];
+        while 1 << ancestors.len() <= max_depth {
+            let previous = ancestors.last().expect("Oops...");
+            let next = previous
+                .iter()
+                .map(|&ancestor| previous[ancestor])
+                .collect();
+            ancestors.push(next);
+        }
+
+        Ok(Self {
+            names,
+            ids,
+            parents,
+            children,
+            depths,
+            subtree_sizes,
+            ancestors,
+        })
+    }
+
+    /// Number of objects.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, object: usize) -> &str {
+        &self.names[object]
+    }
+
+    /// Object `object` directly orbits, `None` for a root.
+    pub fn parent(&self, object: usize) -> Option<usize> {
+        self.parents[object]
+    }
+
+    /// Objects directly orbiting `object`.
+    pub fn children(&self, object: usize) -> &[usize] {
+        &self.children[object]
+    }
+
+    /// Objects orbiting nothing.
+    pub fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len()).filter(|&object| self.parents[object].is_none())
+    }
+
+    /// Number of objects `object` orbits, directly or not.
+    pub fn depth(&self, object: usize) -> usize {
+        self.depths[object]
+    }
+
+    /// Number of objects orbiting `object`, directly or not, plus itself.
+    pub fn subtree_size(&self, object: usize) -> usize {
+        self.subtree_sizes[object]
+    }
+
+    /// Total number of direct and indirect orbits.
+    pub fn total_orbits(&self) -> usize {
+        self.depths.iter().sum()
+    }
+
+    /// Ancestor `levels` levels above `object`, if it is that deep.
+    pub fn ancestor(&self, object: usize, levels: usize) -> Option<usize> {
+        if levels > self.depths[object] {
+            return None;
+        }
+
+        let mut ancestor = object;
+        for (k, jumps) in self.ancestors.iter().enumerate() {
+            if levels & 1 << k != 0 {
+                ancestor = jumps[ancestor];
+            }
+        }
+        Some(ancestor)
+    }
+
+    /// Deepest object both `a` and `b` are, or orbit, if they are in the same tree.
+    pub fn lowest_common_ancestor(&self, a: usize, b: usize) -> Option<usize> {
+        let (a, b) = match self.depths[a] >= self.depths[b] {
+            true => (a, b),
+            false => (b, a),
+        };
+        let mut a = self.ancestor(a, self.depths[a] - self.depths[b])?;
+        let mut b = b;
+        if a == b {
+            return Some(a);
+        }
+
+        for jumps in self.ancestors.iter().rev() {
+            if jumps[a] != jumps[b] {
+                a = jumps[a];
+                b = jumps[b];
+            }
+        }
+        let (a, b) = (self.parents[a]?, self.parents[b]?);
+        (a == b).then_some(a)
+    }
+
+    /// Number of hops between `a` and `b` along the orbits, if they are connected.
+    pub fn distance(&self, a: usize, b: usize) -> Option<usize> {
+        let ancestor = self.lowest_common_ancestor(a, b)?;
+        Some(self.depths[a] + self.depths[b] - 2 * self.depths[ancestor])
+    }
+
+    /// Objects from `a` to `b` along the orbits, both included, if they are
+    /// connected.
+    pub fn path(&self, a: usize, b: usize) -> Option<Vec<usize>> {
+        let ancestor = self.lowest_common_ancestor(a, b)?;
+        let climb = |from: usize| {
+            let mut path = vec![from];
+            while *path.last().expect("Oops...") != ancestor {
+                path.push(self.parents[*path.last().expect("Oops...")].expect("Oops..."));
+            }
+            path
+        };
+
+        let mut path = climb(a);
+        let mut down = climb(b);
+        down.pop();
+        path.extend(down.into_iter().rev());
+        Some(path)
+    }
+
+    /// Number of orbital transfers to move from the object `a` orbits to the one
+    /// `b` orbits, if both orbit something and are connected.
+    pub fn transfers(&self, a: usize, b: usize) -> Option<usize> {
+        self.distance(self.parents[a]?, self.parents[b]?)
+    }
+
+    /// Objects visited by the transfers from the object `a` orbits to the one `b`
+    /// orbits, both included.
+    pub fn transfer_path(&self, a: usize, b: usize) -> Option<Vec<usize>> {
+        self.path(self.parents[a]?, self.parents[b]?)
+    }
+
+    /// Objects `object` can orbit after at most `transfers` orbital transfers,
+    /// counted as in [`OrbitMap::transfers`], closest first: the object it
+    /// orbits, then those at most `transfers` hops away from it.
+    pub fn within(&self, object: usize, transfers: usize) -> Vec<usize> {
+        let Some(center) = self.parents[object] else {
+            return Vec::new();
+        };
+        self.walk(center, Some(transfers))
+            .into_iter()
+            .map(|(other, _)| other)
+            .filter(|&other| other != object)
+            .collect()
+    }
+
+    /// Longest path between two objects along the orbits, in the largest
+    /// connected set of objects if they do not all orbit a common one.
+    pub fn diameter(&self) -> Option<Vec<usize>> {
+        self.roots()
+            .map(|root| {
+                // The object farthest from the root is one end of a longest path,
+                // and the object farthest from it is the other end.
+                let (start, _) = *self.walk(root, None).last().expect("Oops...");
+                let (end, _) = *self.walk(start, None).last().expect("Oops...");
+                self.path(start, end).expect("Oops...")
+            })
+            .max_by_key(Vec::len)
+    }
+
+    /// Objects reached from `object` by following orbits both ways, up to
+    /// `max_hops` hops if given, along with their distance, closest first.
+    fn walk(&self, object: usize, max_hops: Option<usize>) -> Vec<(usize, usize)> {
+        let mut visited = vec![false; self.len()];
+        visited[object] = true;
+        let mut found = vec![(object, 0)];
+
+        let mut index = 0;
+        while index < found.len() {
+            let (current, hops) = found[index];
+            index += 1;
+            if max_hops.is_some_and(|max_hops| hops >= max_hops) {
+                continue;
+            }
+
+            let neighbours = self.parents[current]
+                .iter()
+                .chain(self.children[current].iter());
+            for &neighbour in neighbours {
+                if !visited[neighbour] {
+                    visited[neighbour] = true;
+                    found.push((neighbour, hops + 1));
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Graphviz rendering of the orbits, centers pointing to their orbiters.
+    pub fn to_dot(&self, options: &ExportOptions) -> String {
+        let highlighted = self.highlighted(options);
+        let mut dot = String::from("digraph orbits {\n");
+
+        let mut stack: Vec<usize> = self.roots().collect();
+        while let Some(object) = stack.pop() {
+            let node = quote(&self.names[object]);
+            if highlighted.contains(&object) {
+                _ = writeln!(dot, "  {} [color=red, fontcolor=red];", node);
+            }
+
+            for view in self.views(object, options, &highlighted) {
+                let (child, label) = match view {
+                    View::Object(child) => (child, None),
+                    View::Chain { first, end, length } => (end, Some((first, length))),
+                };
+                let child_node = quote(&self.names[child]);
+                match label {
+                    None => {
+                        let style =
+                            match highlighted.contains(&object) && highlighted.contains(&child) {
+                                true => " [color=red]",
+                                false => "",
+                            };
+                        _ = writeln!(dot, "  {} -> {}{};", node, child_node, style);
+                    }
+                    Some((first, length)) => {
+                        let chain = quote(&format!("{}...", self.names[first]));
+                        _ = writeln!(
+                            dot,
+                            "  {} [label=\"{} objects\", shape=box, style=dashed];",
+                            chain, length
+                        );
+                        _ = writeln!(dot, "  {} -> {} -> {};", node, chain, child_node);
+                    }
+                }
+                stack.push(child);
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Indented rendering of the orbits, two spaces per level, with highlighted
+    /// objects marked with `*`.
+    pub fn to_tree(&self, options: &ExportOptions) -> String {
+        let highlighted = self.highlighted(options);
+        let mut tree = String::new();
+
+        let mut stack: Vec<(View, usize)> =
+            self.roots().map(|root| (View::Object(root), 0)).collect();
+        stack.reverse();
+        while let Some((view, indent)) = stack.pop() {
+            match view {
+                View::Object(object) => {
+                    let mark = if highlighted.contains(&object) {
+                        " *"
+                    } else {
+                        ""
+                    };
+                    _ = writeln!(tree, "{:indent$}{}{}", "", self.names[object], mark);
+                    for view in self.views(object, options, &highlighted).into_iter().rev() {
+                        stack.push((view, indent + 2));
+                    }
+                }
+                View::Chain { end, length, .. } => {
+                    _ = writeln!(tree, "{:indent$}... {} objects", "", length);
+                    stack.push((View::Object(end), indent + 2));
+                }
+            }
+        }
+
+        tree
+    }
+
+    fn highlighted(&self, options: &ExportOptions) -> HashSet<usize> {
+        options
+            .highlight
+            .and_then(|(a, b)| self.path(a, b))
+            .unwrap_or_default()
+            .into_iter()
+            .collect()
+    }
+
+    /// Children of `object` as exported, with long chains of objects orbited
+    /// by a single object each collapsed.
+    fn views(
+        &self,
+        object: usize,
+        options: &ExportOptions,
+        highlighted: &HashSet<usize>,
+    ) -> Vec<View> {
+        let is_link =
+            |object: usize| self.children[object].len() == 1 && !highlighted.contains(&object);
+
+        self.children[object]
+            .iter()
+            .map(|&child| {
+                let mut end = child;
+                let mut length = 0;
+                while is_link(end) {
+                    end = self.children[end][0];
+                    length += 1;
+                }
+                match options.collapse {
+                    Some(min_length) if length >= min_length.max(2) => View::Chain {
+                        first: child,
+                        end,
+                        length,
+                    },
+                    _ => View::Object(child),
+                }
+            })
+            .collect()
+    }
+}
+
+/// How [`OrbitMap::to_dot`] and [`OrbitMap::to_tree`] render the orbits.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExportOptions {
+    /// Objects the path between is highlighted.
+    pub highlight: Option<(usize, usize)>,
+    /// Minimum length, at least 2, of the chains of objects each orbited by a
+    /// single other one that are replaced by a placeholder.
+    pub collapse: Option<usize>,
+}
+
+/// Child of an object, as exported.
+enum View {
+    Object(usize),
+    /// `length` objects collapsed, from `first` to the one `end` orbits.
+    Chain {
+        first: usize,
+        end: usize,
+        length: usize,
+    },
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl FromStr for OrbitMap {
+    type Err = OrbitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let orbits = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.split_once(')')
+                    .ok_or_else(|| OrbitError::InvalidLine(line.to_owned()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(orbits)
+    }
+}
+
+/// Error returned when the orbits do not form trees.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrbitError {
+    /// A line is not of the form `A)B`.
+    InvalidLine(String),
+    /// An object orbits two different centers.
+    MultipleParents {
+        object: String,
+        centers: [String; 2],
+    },
+    /// An object indirectly orbits itself.
+    Cycle(String),
+}
+
+impl fmt::Display for OrbitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLine(line) => write!(f, "invalid orbit {:?}", line),
+            Self::MultipleParents { object, centers } => write!(
+                f,
+                "{} orbits both {} and {}",
+                object, centers[0], centers[1]
+            ),
+            Self::Cycle(object) => write!(f, "{} is part of an orbit cycle", object),
+        }
+    }
+}
+
+impl error::Error for OrbitError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L";
+
+    #[test]
+    fn test_orbits() {
+        let map: OrbitMap = EXAMPLE.parse().unwrap();
+        let id = |name| map.id(name).unwrap();
+        assert_eq!(42, map.total_orbits());
+        assert_eq!(7, map.depth(id("L")));
+        assert_eq!(11, map.subtree_size(id("B")));
+        assert_eq!(Some(id("D")), map.lowest_common_ancestor(id("L"), id("I")));
+        assert_eq!(Some(id("B")), map.lowest_common_ancestor(id("H"), id("F")));
+        assert_eq!(Some(id("E")), map.lowest_common_ancestor(id("E"), id("L")));
+        assert_eq!(Some(6), map.distance(id("H"), id("F")));
+        assert_eq!(Some(id("C")), map.ancestor(id("J"), 3));
+    }
+
+    #[test]
+    fn test_export() {
+        let map: OrbitMap = "COM)A\nA)B\nB)C\nC)D\nC)E".parse().unwrap();
+        let id = |name| map.id(name).unwrap();
+        assert_eq!(
+            Some(vec![id("D"), id("C"), id("E")]),
+            map.path(id("D"), id("E"))
+        );
+
+        let options = ExportOptions {
+            highlight: Some((id("D"), id("E"))),
+            collapse: Some(2),
+        };
+        assert_eq!(
+            "COM\n  ... 2 objects\n    C *\n      D *\n      E *\n",
+            map.to_tree(&options)
+        );
+        assert!(
+            map.to_dot(&options)
+                .contains("\"COM\" -> \"A...\" -> \"C\";")
+        );
+    }
+
+    #[test]
+    fn test_queries() {
+        let map: OrbitMap = format!("{}\nK)YOU\nI)SAN", EXAMPLE).parse().unwrap();
+        let id = |name| map.id(name).unwrap();
+        let names = |objects: Vec<usize>| -> Vec<&str> {
+            objects.into_iter().map(|object| map.name(object)).collect()
+        };
+
+        assert_eq!(Some(4), map.transfers(id("YOU"), id("SAN")));
+        assert_eq!(
+            vec!["K", "J", "E", "D", "I"],
+            names(map.transfer_path(id("YOU"), id("SAN")).unwrap())
+        );
+        assert_eq!(vec!["K"], names(map.within(id("YOU"), 0)));
+        assert_eq!(vec!["K", "J", "L"], names(map.within(id("YOU"), 1)));
+        let mut near = names(map.within(id("E"), 1));
+        near.sort();
+        assert_eq!(vec!["C", "D", "I"], near);
+        assert!(names(map.within(id("YOU"), 4)).contains(&"I"));
+        assert!(map.within(id("COM"), 1).is_empty());
+        assert_eq!(8, map.diameter().unwrap().len() - 1);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Some(OrbitError::MultipleParents {
+                object: String::from("B"),
+                centers: [String::from("A"), String::from("C")]
+            }),
+            "A)B\nC)B".parse::<OrbitMap>().err()
+        );
+        assert!(matches!(
+            "COM)A\nB)C\nC)B".parse::<OrbitMap>(),
+            Err(OrbitError::Cycle(_))
+        ));
+        assert_eq!(
+            Some(OrbitError::Cycle(String::from("A"))),
+            "D)E\nA)D\nB)A\nA)B".parse::<OrbitMap>().err()
+        );
+        assert!(matches!(
+            "COM-A".parse::<OrbitMap>(),
+            Err(OrbitError::InvalidLine(_))
+        ));
+        assert_eq!(None, "A)B\nC)D".parse::<OrbitMap>().unwrap().distance(1, 3));
+    }
+}
@@ -0,0 +1,53 @@
+use std::{any, error, fmt, hash::Hash, num::ParseIntError, str::FromStr};
+
+use num::{Integer, PrimInt, Signed, traits::NumAssign};
+
+/// Signed integer type usable as the component of coordinates and vectors.
+pub trait Scalar:
+    PrimInt
+    + Signed
+    + Integer
+    + NumAssign
+    + Hash
+    + Default
+    + FromStr<Err = ParseIntError>
+    + fmt::Debug
+    + fmt::Display
+{
+}
+
+impl<T> Scalar for T where
+    T: PrimInt
+        + Signed
+        + Integer
+        + NumAssign
+        + Hash
+        + Default
+        + FromStr<Err = ParseIntError>
+        + fmt::Debug
+        + fmt::Display
+{
+}
+
+/// Error returned when a value does not fit in the integer type it is converted to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutOfRange {
+    value: String,
+    target: &'static str,
+}
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} does not fit in {}", self.value, self.target)
+    }
+}
+
+impl error::Error for OutOfRange {}
+
+/// Converts `value` to another integer type, failing instead of truncating.
+pub fn cast<T: Scalar, U: Scalar>(value: T) -> Result<U, OutOfRange> {
+    U::from(value).ok_or_else(|| OutOfRange {
+        value: value.to_string(),
+        target: any::type_name::<U>(),
+    })
+}
@@ -0,0 +1,384 @@
+use std::{error, fmt};
+
+use crate::{coordinates::Coordinates, grid::Grid};
+
+pub const BLACK: u8 = 0;
+pub const WHITE: u8 = 1;
+pub const TRANSPARENT: u8 = 2;
+
+/// Image in the Space Image Format: layers of digits, stacked with the first
+/// layer in front.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    layers: Vec<Grid<u8>>,
+}
+
+impl Image {
+    /// Builds an image from layers, which must all be `width` by `height`.
+    pub fn new(width: usize, height: usize, layers: Vec<Grid<u8>>) -> Result<Self, SifError> {
+        if width == 0 || height == 0 {
+            return Err(SifError::EmptyDimensions);
+        }
+        if let Some(layer) = layers
+            .iter()
+            .find(|layer| layer.width() != width || layer.height() != height)
+        {
+            return Err(SifError::LayerSize {
+                expected: (width, height),
+                found: (layer.width(), layer.height()),
+            });
+        }
+
+        Ok(Self {
+            width,
+            height,
+            layers,
+        })
+    }
+
+    /// Parses the digits of a `width` by `height` image, ignoring surrounding
+    /// whitespace.
+    pub fn parse(data: &str, width: usize, height: usize) -> Result<Self, SifError> {
+        if width == 0 || height == 0 {
+            return Err(SifError::EmptyDimensions);
+        }
+
+        let pixels = data
+            .trim()
+            .chars()
+            .map(|c| {
+                c.to_digit(10)
+                    .map(|digit| digit as u8)
+                    .ok_or(SifError::InvalidPixel(c))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let layer_size = width * height;
+        if pixels.is_empty() || pixels.len() % layer_size != 0 {
+            return Err(SifError::Length {
+                length: pixels.len(),
+                layer_size,
+            });
+        }
+
+        let layers = pixels
+            .chunks(layer_size)
+            .map(|layer| Grid::from_vec(width, height, layer.to_vec()))
+            .collect();
+        Self::new(width, height, layers)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn layers(&self) -> &[Grid<u8>] {
+        &self.layers
+    }
+
+    /// Flattens the layers, each pixel taking the colour of the first layer
+    /// where it is not transparent, or `fallback` when it is transparent in
+    /// every layer.
+    pub fn decode(&self, fallback: u8) -> Grid<u8> {
+        let pixels = (0..self.width * self.height)
+            .map(|pixel| {
+                self.layers
+                    .iter()
+                    .map(|layer| layer.cells()[pixel])
+                    .find(|&colour| colour != TRANSPARENT)
+                    .unwrap_or(fallback)
+            })
+            .collect();
+
+        Grid::from_vec(self.width, self.height, pixels)
+    }
+
+    /// Number of pixels of each colour in `layer`, indexed by colour.
+    pub fn histogram(&self, layer: usize) -> [usize; 10] {
+        let mut histogram = [0; 10];
+        for &colour in self.layers[layer].cells() {
+            histogram[usize::from(colour)] += 1;
+        }
+        histogram
+    }
+
+    pub fn histograms(&self) -> Vec<[usize; 10]> {
+        (0..self.layers.len())
+            .map(|layer| self.histogram(layer))
+            .collect()
+    }
+
+    /// First layer with the fewest pixels of `colour`.
+    pub fn layer_with_fewest(&self, colour: u8) -> Option<usize> {
+        (0..self.layers.len()).min_by_key(|&layer| self.histogram(layer)[usize::from(colour)])
+    }
+
+    /// First layer with the most pixels of `colour`.
+    pub fn layer_with_most(&self, colour: u8) -> Option<usize> {
+        (0..self.layers.len())
+            .rev()
+            .max_by_key(|&layer| self.histogram(layer)[usize::from(colour)])
+    }
+
+    /// Layer each decoded pixel comes from, `None` where every layer is
+    /// transparent.
+    pub fn winners(&self) -> Grid<Option<usize>> {
+        let pixels = (0..self.width * self.height)
+            .map(|pixel| {
+                self.layers
+                    .iter()
+                    .position(|layer| layer.cells()[pixel] != TRANSPARENT)
+            })
+            .collect();
+
+        Grid::from_vec(self.width, self.height, pixels)
+    }
+
+    /// Pixels differing between layers `a` and `b`.
+    pub fn diff_layers(&self, a: usize, b: usize) -> Vec<Difference> {
+        diff(&self.layers[a], &self.layers[b])
+    }
+
+    /// Pixels differing between the layers of `self` and `other`, for every
+    /// layer with differences. Both images must have the same dimensions and
+    /// number of layers.
+    pub fn diff(&self, other: &Image) -> Result<Vec<(usize, Vec<Difference>)>, SifError> {
+        if (self.width, self.height) != (other.width, other.height) {
+            return Err(SifError::LayerSize {
+                expected: (self.width, self.height),
+                found: (other.width, other.height),
+            });
+        }
+        if self.layers.len() != other.layers.len() {
+            return Err(SifError::LayerCount {
+                expected: self.layers.len(),
+                found: other.layers.len(),
+            });
+        }
+
+        Ok(self
+            .layers
+            .iter()
+            .zip(other.layers.iter())
+            .map(|(layer, other)| diff(layer, other))
+            .enumerate()
+            .filter(|(_, differences)| !differences.is_empty())
+            .collect())
+    }
+
+    /// Digits of the layers, one after the other.
+    pub fn encode(&self) -> String {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.cells())
+            .map(|&digit| char::from(b'0' + digit))
+            .collect()
+    }
+}
+
+/// Pixel with different colours in two layers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Difference {
+    pub position: Coordinates,
+    pub before: u8,
+    pub after: u8,
+}
+
+/// Pixels differing between two layers of the same size.
+pub fn diff(before: &Grid<u8>, after: &Grid<u8>) -> Vec<Difference> {
+    assert_eq!(
+        (before.width(), before.height()),
+        (after.width(), after.height()),
+        "Layers of different sizes"
+    );
+
+    before
+        .iter()
+        .zip(after.cells())
+        .filter(|((_, before), after)| before != after)
+        .map(|((position, &before), &after)| Difference {
+            position,
+            before,
+            after,
+        })
+        .collect()
+}
+
+/// Plain-text Netpbm formats decoded images can be exported to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Netpbm {
+    /// Black and white bitmap, transparent pixels showing as black.
+    Pbm,
+    /// Greyscale, transparent pixels showing as grey.
+    Pgm,
+    /// Colour, transparent pixels showing as magenta.
+    Ppm,
+}
+
+impl Netpbm {
+    /// Format with the usual extension `extension`.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "pbm" => Some(Self::Pbm),
+            "pgm" => Some(Self::Pgm),
+            "ppm" => Some(Self::Ppm),
+            _ => None,
+        }
+    }
+
+    /// Writes a decoded image in this format.
+    pub fn export(&self, image: &Grid<u8>) -> String {
+        let header = match self {
+            Self::Pbm => format!("P1\n{} {}\n", image.width(), image.height()),
+            Self::Pgm => format!("P2\n{} {}\n2\n", image.width(), image.height()),
+            Self::Ppm => format!("P3\n{} {}\n255\n", image.width(), image.height()),
+        };
+
+        let rows = image.rows().map(|row| {
+            row.iter()
+                .map(|&colour| self.pixel(colour))
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        let mut export = header;
+        for row in rows {
+            export.push_str(&row);
+            export.push('\n');
+        }
+        export
+    }
+
+    fn pixel(&self, colour: u8) -> &'static str {
+        match (self, colour) {
+            // In PBM files, 1 is black.
+            (Self::Pbm, WHITE) => "0",
+            (Self::Pbm, _) => "1",
+            (Self::Pgm, BLACK) => "0",
+            (Self::Pgm, WHITE) => "2",
+            (Self::Pgm, _) => "1",
+            (Self::Ppm, BLACK) => "0 0 0",
+            (Self::Ppm, WHITE) => "255 255 255",
+            (Self::Ppm, _) => "255 0 255",
+        }
+    }
+}
+
+/// Error returned when data is not a valid SIF image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SifError {
+    /// The width or height is zero.
+    EmptyDimensions,
+    /// A pixel is not a digit.
+    InvalidPixel(char),
+    /// The data is empty or does not split into whole layers.
+    Length { length: usize, layer_size: usize },
+    /// A layer does not have the size of the image.
+    LayerSize {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    /// Images do not have the same number of layers.
+    LayerCount { expected: usize, found: usize },
+}
+
+impl fmt::Display for SifError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyDimensions => write!(f, "images must be at least 1x1"),
+            Self::InvalidPixel(c) => write!(f, "invalid pixel {:?}", c),
+            Self::Length { length, layer_size } => write!(
+                f,
+                "{} pixels do not make whole layers of {} pixels",
+                length, layer_size
+            ),
+            Self::LayerSize { expected, found } => write!(
+                f,
+                "layer is {}x{} instead of {}x{}",
+                found.0, found.1, expected.0, expected.1
+            ),
+            Self::LayerCount { expected, found } => {
+                write!(f, "image has {} layers instead of {}", found, expected)
+            }
+        }
+    }
+}
+
+impl error::Error for SifError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let image = Image::parse("0222112222120000\n", 2, 2).unwrap();
+        assert_eq!(4, image.layers().len());
+        assert_eq!(vec![0, 1, 1, 0], image.decode(BLACK).cells());
+        assert_eq!("0222112222120000", image.encode());
+
+        let transparent = Image::parse("2212", 2, 1).unwrap();
+        assert_eq!(vec![1, 2], transparent.decode(TRANSPARENT).cells());
+    }
+
+    #[test]
+    fn test_analysis() {
+        let image = Image::parse("0222112222120000", 2, 2).unwrap();
+        assert_eq!([1, 0, 3, 0, 0, 0, 0, 0, 0, 0], image.histogram(0));
+        assert_eq!(Some(1), image.layer_with_fewest(0));
+        assert_eq!(Some(3), image.layer_with_most(0));
+        assert_eq!(
+            vec![Some(0), Some(1), Some(2), Some(3)],
+            image.winners().cells()
+        );
+        assert_eq!(
+            vec![Difference {
+                position: Coordinates::new(0, 0),
+                before: 1,
+                after: 2
+            }],
+            image.diff_layers(1, 2)[..1]
+        );
+
+        let other = Image::parse("0222112222120001", 2, 2).unwrap();
+        let differences = image.diff(&other).unwrap();
+        assert_eq!(1, differences.len());
+        assert_eq!(3, differences[0].0);
+        assert_eq!(
+            Err(SifError::LayerCount {
+                expected: 4,
+                found: 1
+            }),
+            image.diff(&Image::parse("0000", 2, 2).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Err(SifError::EmptyDimensions), Image::parse("0000", 0, 2));
+        assert_eq!(Err(SifError::InvalidPixel('x')), Image::parse("01x1", 2, 2));
+        assert_eq!(
+            Err(SifError::Length {
+                length: 5,
+                layer_size: 4
+            }),
+            Image::parse("01011", 2, 2)
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let image = Image::parse("0122", 2, 2).unwrap().decode(TRANSPARENT);
+        assert_eq!("P1\n2 2\n1 0\n1 1\n", Netpbm::Pbm.export(&image));
+        assert_eq!("P2\n2 2\n2\n0 2\n1 1\n", Netpbm::Pgm.export(&image));
+        assert!(
+            Netpbm::Ppm
+                .export(&image)
+                .ends_with("255 0 255 255 0 255\n")
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use std::{array, error, fmt, ops, str::FromStr};
+
+use crate::scalar::{self, OutOfRange, Scalar};
+
+/// Names of the first axes, as used by the `<x=.., y=.., z=..>` format.
+const AXES: [&str; 4] = ["x", "y", "z", "w"];
+
+/// Vector with `N` components, `i64` unless another signed integer type is
+/// picked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VecN<const N: usize, T = i64> {
+    components: [T; N],
+}
+
+pub type Vec2D<T = i64> = VecN<2, T>;
+pub type Vec3D<T = i64> = VecN<3, T>;
+pub type Vec4D<T = i64> = VecN<4, T>;
+
+impl<const N: usize, T: Scalar> Default for VecN<N, T> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<const N: usize, T: Scalar> VecN<N, T> {
+    pub fn new(components: [T; N]) -> Self {
+        Self { components }
+    }
+
+    pub fn zero() -> Self {
+        Self::new([T::zero(); N])
+    }
+
+    pub fn components(&self) -> &[T; N] {
+        &self.components
+    }
+
+    /// Converts the components to another integer type, failing if any of them
+    /// does not fit.
+    pub fn cast<U: Scalar>(&self) -> Result<VecN<N, U>, OutOfRange> {
+        let mut components = [U::zero(); N];
+        for (component, value) in components.iter_mut().zip(self.components) {
+            *component = scalar::cast(value)?;
+        }
+        Ok(VecN::new(components))
+    }
+
+    pub fn signum(&self) -> Self {
+        self.map(|value| value.signum())
+    }
+
+    pub fn abs(&self) -> Self {
+        self.map(|value| value.abs())
+    }
+
+    /// Component-wise minimum.
+    pub fn min(&self, other: &Self) -> Self {
+        self.zip(other, |a, b| a.min(b))
+    }
+
+    /// Component-wise maximum.
+    pub fn max(&self, other: &Self) -> Self {
+        self.zip(other, |a, b| a.max(b))
+    }
+
+    pub fn dot(&self, other: &Self) -> T {
+        self.components
+            .iter()
+            .zip(other.components)
+            .fold(T::zero(), |sum, (&a, b)| sum + a * b)
+    }
+
+    /// Sum of the absolute values of the components.
+    pub fn manhattan(&self) -> T {
+        self.components
+            .iter()
+            .fold(T::zero(), |sum, value| sum + value.abs())
+    }
+
+    fn map(&self, f: impl Fn(T) -> T) -> Self {
+        Self::new(self.components.map(f))
+    }
+
+    fn zip(&self, other: &Self, f: impl Fn(T, T) -> T) -> Self {
+        Self::new(array::from_fn(|i| {
+            f(self.components[i], other.components[i])
+        }))
+    }
+}
+
+impl<const N: usize, T: Scalar> From<[T; N]> for VecN<N, T> {
+    fn from(components: [T; N]) -> Self {
+        Self::new(components)
+    }
+}
+
+impl<const N: usize, T> ops::Index<usize> for VecN<N, T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.components[index]
+    }
+}
+
+impl<const N: usize, T> ops::IndexMut<usize> for VecN<N, T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.components[index]
+    }
+}
+
+/// Name of the axis at `index`: `x`, `y`, `z`, `w`, then `x4`, `x5`...
+pub fn axis(index: usize) -> String {
+    AXES.get(index)
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format!("x{}", index))
+}
+
+impl<const N: usize, T: Scalar> fmt::Display for VecN<N, T> {
+    /// Formats the vector as `<x=1, y=2, z=3>`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<")?;
+        for (index, value) in self.components.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}={}", axis(index), value)?;
+        }
+        write!(f, ">")
+    }
+}
+
+impl<const N: usize, T: Scalar> FromStr for VecN<N, T> {
+    type Err = ParseVecError;
+
+    /// Parses the `<x=1, y=2, z=3>` format, expecting exactly `N` components in
+    /// axis order.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |reason: &str| ParseVecError(format!("{}: {}", reason, s));
+
+        let inner = s
+            .trim()
+            .strip_prefix('<')
+            .and_then(|s| s.strip_suffix('>'))
+            .ok_or_else(|| error("expected <...>"))?;
+
+        let mut components = [T::zero(); N];
+        let mut fields = inner.split(',').map(str::trim);
+        for (index, component) in components.iter_mut().enumerate() {
+            let field = fields
+                .next()
+                .ok_or_else(|| error(&format!("expected {} components", N)))?;
+            let value = field
+                .strip_prefix(&axis(index))
+                .and_then(|field| field.strip_prefix('='))
+                .ok_or_else(|| error(&format!("expected {}=<value>", axis(index))))?;
+            *component = value
+                .parse()
+                .map_err(|e| error(&format!("invalid {} ({})", axis(index), e)))?;
+        }
+
+        match fields.next() {
+            Some(_) => Err(error(&format!("expected {} components", N))),
+            None => Ok(Self::new(components)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseVecError(String);
+
+impl fmt::Display for ParseVecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid vector {}", self.0)
+    }
+}
+
+impl error::Error for ParseVecError {}
+
+/// Implements a component-wise binary operator for every combination of owned
+/// and borrowed operands, along with its assigning variant.
+macro_rules! component_wise {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl<const N: usize, T: Scalar> ops::$trait<&VecN<N, T>> for &VecN<N, T> {
+            type Output = VecN<N, T>;
+
+            fn $method(self, rhs: &VecN<N, T>) -> Self::Output {
+                self.zip(rhs, |a, b| a $op b)
+            }
+        }
+
+        impl<const N: usize, T: Scalar> ops::$trait<VecN<N, T>> for &VecN<N, T> {
+            type Output = VecN<N, T>;
+
+            fn $method(self, rhs: VecN<N, T>) -> Self::Output {
+                self $op &rhs
+            }
+        }
+
+        impl<const N: usize, T: Scalar> ops::$trait<&VecN<N, T>> for VecN<N, T> {
+            type Output = VecN<N, T>;
+
+            fn $method(self, rhs: &VecN<N, T>) -> Self::Output {
+                &self $op rhs
+            }
+        }
+
+        impl<const N: usize, T: Scalar> ops::$trait for VecN<N, T> {
+            type Output = VecN<N, T>;
+
+            fn $method(self, rhs: VecN<N, T>) -> Self::Output {
+                &self $op &rhs
+            }
+        }
+
+        impl<const N: usize, T: Scalar> ops::$assign_trait<&VecN<N, T>> for VecN<N, T> {
+            fn $assign_method(&mut self, rhs: &VecN<N, T>) {
+                *self = &*self $op rhs;
+            }
+        }
+
+        impl<const N: usize, T: Scalar> ops::$assign_trait for VecN<N, T> {
+            fn $assign_method(&mut self, rhs: VecN<N, T>) {
+                *self = &*self $op &rhs;
+            }
+        }
+    };
+}
+
+component_wise!(Add, add, AddAssign, add_assign, +);
+component_wise!(Sub, sub, SubAssign, sub_assign, -);
+
+impl<const N: usize, T: Scalar> ops::Mul<T> for &VecN<N, T> {
+    type Output = VecN<N, T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        self.map(|value| value * rhs)
+    }
+}
+
+impl<const N: usize, T: Scalar> ops::Mul<T> for VecN<N, T> {
+    type Output = VecN<N, T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        self.map(|value| value * rhs)
+    }
+}
+
+impl<const N: usize, T: Scalar> ops::MulAssign<T> for VecN<N, T> {
+    fn mul_assign(&mut self, rhs: T) {
+        *self = self.map(|value| value * rhs);
+    }
+}
+
+impl<const N: usize, T: Scalar> ops::Neg for &VecN<N, T> {
+    type Output = VecN<N, T>;
+
+    fn neg(self) -> Self::Output {
+        self.map(|value| -value)
+    }
+}
+
+impl<const N: usize, T: Scalar> ops::Neg for VecN<N, T> {
+    type Output = VecN<N, T>;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let vec = Vec3D::new([-1, 0, 2]);
+        assert_eq!(String::from("<x=-1, y=0, z=2>"), format!("{}", vec));
+    }
+
+    #[test]
+    fn test_parse() {
+        let vec: Vec3D = "<x=-1, y=0, z=2>".parse().unwrap();
+        assert_eq!(Vec3D::new([-1, 0, 2]), vec);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("<x=-1, y=0>".parse::<Vec3D>().is_err());
+        assert!("<x=-1, y=0, z=2, w=3>".parse::<Vec3D>().is_err());
+        assert!("<x=-1, z=0, y=2>".parse::<Vec3D>().is_err());
+        assert!("<x=a, y=0, z=2>".parse::<Vec3D>().is_err());
+        assert_eq!(Ok(Vec4D::new([1, 2, 3, 4])), "<x=1, y=2, z=3, w=4>".parse());
+    }
+
+    #[test]
+    fn test_operators() {
+        let a = Vec2D::new([3, -4]);
+        let b = Vec2D::new([-1, 2]);
+        assert_eq!(Vec2D::new([2, -2]), a + b);
+        assert_eq!(Vec2D::new([4, -6]), a - b);
+        assert_eq!(Vec2D::new([-3, 4]), -a);
+        assert_eq!(Vec2D::new([6, -8]), a * 2);
+        assert_eq!(-11, a.dot(&b));
+        assert_eq!(7, a.manhattan());
+        assert_eq!(Vec2D::new([-1, -4]), a.min(&b));
+        assert_eq!(Vec2D::new([3, 4]), a.abs());
+    }
+
+    #[test]
+    fn test_cast() {
+        let vec: Vec3D<i8> = Vec3D::new([-1, 0, 127]).cast().unwrap();
+        assert_eq!(Vec3D::new([-1, 0, 127]), vec);
+        assert!(Vec3D::new([0, 128, 0]).cast::<i8>().is_err());
+    }
+}